@@ -6,4 +6,5 @@ source ./token-from-file.sh
 
 time ./target/release/bench \
 	--log-file bench-direct-time-server.log \
-	--server ./target/release/mcp-time-server
+	--server ./target/release/mcp-time-server \
+	--stdio
@@ -10,7 +10,7 @@ source ./token-from-file.sh
 /usr/bin/time -v ./target/release/bench \
 	--silent \
 	--server $HOME/prj/mcp-stdio-wrapper/target/release/mcp_stdio_wrapper  \
-	--url "https://localhost:3000/mcp/" \
+	--url "${MCP_URL:-https://localhost:3000/mcp/}" \
 	--auth "$AUTH" \
 	--concurrency 80 \
 	--insecure \
@@ -11,7 +11,7 @@ RUST_BACKTRACE=1
 /usr/bin/time -v ./target/release/bench8 \
         --silent \
 	--server $HOME/prj/mcp-stdio-wrapper/target/release/mcp_stdio_wrapper  \
-	--url "http://localhost:3000/mcp/" \
+	--url "${MCP_URL:-http://localhost:3000/mcp/}" \
 	--auth "$AUTH" \
 	--concurrency 150 \
 	--log-level off \
@@ -22,7 +22,7 @@ echo ""
 
 # Check if server is running
 echo -e "${YELLOW}Checking if server is running...${NC}"
-if ! curl -s -o /dev/null -w "%{http_code}" "$URL" > /dev/null 2>&1; then
+if ! curl -sf -o /dev/null "${URL%/mcp}/readyz"; then
     echo -e "${YELLOW}Warning: Server might not be running at $URL${NC}"
     echo -e "${YELLOW}Please start the server with: cargo run --release${NC}"
     echo ""
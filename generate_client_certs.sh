@@ -0,0 +1,28 @@
+#!/usr/bin/env bash
+
+set -euo pipefail
+
+if ! command -v openssl &>/dev/null; then
+  echo "Error: openssl is not installed. Please install it to generate certificates." >&2
+  exit 1
+fi
+
+CLIENT_CN="${1:-mcp-client}"
+
+echo "Generating client CA (client-ca.pem, client-ca-key.pem)..."
+openssl req -x509 -newkey rsa:2048 -keyout client-ca-key.pem -out client-ca.pem -days 365 -nodes \
+  -subj "/CN=MCP Test Client CA" \
+  -addext "basicConstraints=critical,CA:TRUE" \
+  -addext "keyUsage=critical,keyCertSign"
+
+echo "Generating client certificate for CN=${CLIENT_CN} (client.pem, client-key.pem)..."
+openssl req -newkey rsa:2048 -keyout client-key.pem -out client.csr -nodes \
+  -subj "/CN=${CLIENT_CN}"
+openssl x509 -req -in client.csr -CA client-ca.pem -CAkey client-ca-key.pem -CAcreateserial \
+  -out client.pem -days 365 \
+  -extfile <(printf "basicConstraints=CA:FALSE\nextendedKeyUsage=clientAuth\n")
+rm -f client.csr client-ca.srl
+
+echo "Client certificates generated successfully:"
+echo "  - client-ca.pem (pass to --tls-client-ca)"
+echo "  - client.pem / client-key.pem (pass to curl --cert / --key)"
@@ -0,0 +1,8 @@
+#!/usr/bin/env -S bash
+
+
+set -xueo pipefail
+
+# Plaintext on 3000 and TLS on 3443 from one process, for comparing
+# bench-wrapper.sh against MCP_URL=https://localhost:3443/mcp/ bench-wrapper-tls.sh.
+tmux new-session -d -s mcp-time-server 'cargo run --release --bin mcp-time-server -- --tls-cert cert.pem --tls-key key.pem --tls-port 3443 "$@"'
@@ -0,0 +1,181 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::Arc;
+
+// --- Configuration ---
+
+/// Identity behind an accepted bearer token, attached as a request extension:
+/// the JWT `sub` claim, or `token#N` for the N-th static token.
+#[derive(Clone, Debug)]
+pub struct BearerSubject(pub String);
+
+#[derive(Deserialize)]
+struct Claims {
+    sub: Option<String>,
+}
+
+/// Bearer-token checks applied to the MCP routes. A request is accepted if its
+/// token matches any static token or verifies as a JWT under any configured key.
+pub struct Auth {
+    tokens: Vec<String>,
+    jwt_keys: Vec<(DecodingKey, Validation)>,
+}
+
+impl Auth {
+    /// Builds the verifier from the CLI options. Returns `Ok(None)` when no
+    /// token or key is configured, leaving the routes open.
+    pub fn from_options(
+        tokens: Vec<String>,
+        hs256_secret: Option<&str>,
+        rs256_public_key: Option<&Path>,
+        audience: &[String],
+    ) -> anyhow::Result<Option<Self>> {
+        let validation = |algorithm| {
+            let mut validation = Validation::new(algorithm);
+            if audience.is_empty() {
+                validation.validate_aud = false;
+            } else {
+                validation.set_audience(audience);
+            }
+            validation
+        };
+
+        let mut jwt_keys = Vec::new();
+        if let Some(secret) = hs256_secret {
+            jwt_keys.push((
+                DecodingKey::from_secret(secret.as_bytes()),
+                validation(Algorithm::HS256),
+            ));
+        }
+        if let Some(path) = rs256_public_key {
+            let pem = std::fs::read(path)?;
+            jwt_keys.push((
+                DecodingKey::from_rsa_pem(&pem)?,
+                validation(Algorithm::RS256),
+            ));
+        }
+
+        if tokens.is_empty() && jwt_keys.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Auth { tokens, jwt_keys }))
+    }
+
+    fn verify(&self, token: &str) -> Result<Option<BearerSubject>, &'static str> {
+        if let Some(index) = self
+            .tokens
+            .iter()
+            .position(|expected| constant_time_eq(expected.as_bytes(), token.as_bytes()))
+        {
+            return Ok(Some(BearerSubject(format!("token#{index}"))));
+        }
+
+        let mut failure = "invalid token";
+        for (key, validation) in &self.jwt_keys {
+            match jsonwebtoken::decode::<Claims>(token, key, validation) {
+                Ok(data) => return Ok(data.claims.sub.map(BearerSubject)),
+                Err(e) => {
+                    use jsonwebtoken::errors::ErrorKind;
+                    // Report the most specific reason across the configured keys.
+                    match e.kind() {
+                        ErrorKind::ExpiredSignature => failure = "token expired",
+                        ErrorKind::InvalidAudience => failure = "invalid audience",
+                        ErrorKind::MissingRequiredClaim(_) if failure == "invalid token" => {
+                            failure = "missing required claim";
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        Err(failure)
+    }
+}
+
+// --- Middleware ---
+
+/// Rejects requests without a valid `Authorization: Bearer` token with 401,
+/// and tags accepted ones with their [`BearerSubject`].
+pub async fn require_bearer(
+    State(auth): State<Arc<Auth>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(bearer_token);
+
+    let result = match token {
+        Some(token) => auth.verify(token),
+        None => Err("missing bearer token"),
+    };
+    match result {
+        Ok(subject) => {
+            if let Some(subject) = subject {
+                request.extensions_mut().insert(subject);
+            }
+            next.run(request).await
+        }
+        Err(reason) => unauthorized(token.is_some(), reason),
+    }
+}
+
+/// Credentials of a `Bearer` authorization. The scheme name is
+/// case-insensitive (RFC 7235).
+fn bearer_token(value: &str) -> Option<&str> {
+    let (scheme, token) = value.split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("Bearer")
+        .then_some(token.trim())
+}
+
+fn unauthorized(token_present: bool, reason: &str) -> Response {
+    let mut response = crate::create_transport_error(
+        StatusCode::UNAUTHORIZED,
+        -32000,
+        &format!("Unauthorized: {reason}"),
+    );
+    // RFC 6750: omit the error code when the request had no credentials at all.
+    let challenge = if token_present {
+        format!(r#"Bearer realm="mcp", error="invalid_token", error_description="{reason}""#)
+    } else {
+        r#"Bearer realm="mcp""#.to_string()
+    };
+    response.headers_mut().insert(
+        header::WWW_AUTHENTICATE,
+        HeaderValue::from_str(&challenge).unwrap(),
+    );
+    response
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bearer_scheme_is_case_insensitive() {
+        assert_eq!(bearer_token("Bearer abc"), Some("abc"));
+        assert_eq!(bearer_token("bearer abc"), Some("abc"));
+        assert_eq!(bearer_token("BEARER  abc "), Some("abc"));
+    }
+
+    #[test]
+    fn other_schemes_are_not_bearer_tokens() {
+        assert_eq!(bearer_token("Basic YWxhZGRpbjpvcGVuc2VzYW1l"), None);
+        assert_eq!(bearer_token("Bearerabc"), None);
+        assert_eq!(bearer_token("Bearer"), None);
+    }
+}
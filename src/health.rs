@@ -0,0 +1,59 @@
+use crate::protocol::ProtocolVersion;
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Whether the server should receive traffic: set once every listener is
+/// bound, cleared again when shutdown begins.
+#[derive(Clone, Debug, Default)]
+pub struct Readiness(Arc<AtomicBool>);
+
+impl Readiness {
+    pub fn set(&self, ready: bool) {
+        self.0.store(ready, Ordering::Release);
+    }
+
+    fn is_ready(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// Probe routes. They are mounted outside the bearer-auth layer so that
+/// orchestrators and scripts can poll them without credentials.
+pub fn router(readiness: Readiness) -> Router {
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/version", get(version))
+        .with_state(readiness)
+}
+
+/// GET `/healthz`: the process is up and serving HTTP.
+async fn healthz() -> &'static str {
+    "ok"
+}
+
+/// GET `/readyz`: 200 while accepting MCP traffic, 503 before startup
+/// completes and while draining.
+async fn readyz(State(readiness): State<Readiness>) -> (StatusCode, &'static str) {
+    if readiness.is_ready() {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "not ready")
+    }
+}
+
+/// GET `/version`: build and protocol information.
+async fn version() -> Json<Value> {
+    let protocol_versions: Vec<&str> = ProtocolVersion::SUPPORTED
+        .into_iter()
+        .map(ProtocolVersion::as_str)
+        .collect();
+    Json(serde_json::json!({
+        "name": env!("CARGO_PKG_NAME"),
+        "version": env!("CARGO_PKG_VERSION"),
+        "protocolVersions": protocol_versions,
+        "latestProtocolVersion": ProtocolVersion::LATEST.as_str()
+    }))
+}
@@ -0,0 +1,157 @@
+/// Deepest nesting `serde_json` parses; anything deeper fails its recursion
+/// limit as a parse error, so `--max-json-depth` may not exceed it.
+pub const MAX_SUPPORTED_DEPTH: usize = 127;
+
+/// Bounds on incoming messages, checked before they are dispatched.
+#[allow(clippy::struct_field_names)]
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    /// Largest accepted HTTP body or stdio line, in bytes.
+    pub max_body_bytes: usize,
+    /// Deepest accepted nesting of JSON arrays and objects.
+    pub max_depth: usize,
+    /// Most messages accepted in one JSON-RPC batch.
+    pub max_batch: usize,
+}
+
+impl Limits {
+    /// Checks the nesting depth of a raw JSON document without parsing it, so
+    /// deeply nested input is rejected before any `Value` tree is built.
+    /// Brackets inside strings are skipped; malformed input is left for the
+    /// parser to report.
+    pub fn check_depth(&self, json: &[u8]) -> Result<(), String> {
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+        for &byte in json {
+            if in_string {
+                match byte {
+                    _ if escaped => escaped = false,
+                    b'\\' => escaped = true,
+                    b'"' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match byte {
+                b'"' => in_string = true,
+                b'[' | b'{' => {
+                    depth += 1;
+                    if depth > self.max_depth {
+                        return Err(format!(
+                            "Invalid Request: JSON nesting exceeds the limit of {}",
+                            self.max_depth
+                        ));
+                    }
+                }
+                b']' | b'}' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    pub fn check_batch(&self, len: usize) -> Result<(), String> {
+        if len > self.max_batch {
+            return Err(format!(
+                "Invalid Request: batch of {len} messages exceeds the limit of {}",
+                self.max_batch
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn body_too_large_message(&self) -> String {
+        format!(
+            "Invalid Request: message exceeds the limit of {} bytes",
+            self.max_body_bytes
+        )
+    }
+}
+
+/// Parses a `--max-json-depth` value, between 1 and [`MAX_SUPPORTED_DEPTH`].
+pub fn parse_depth(value: &str) -> Result<usize, String> {
+    value
+        .parse::<usize>()
+        .ok()
+        .filter(|depth| (1..=MAX_SUPPORTED_DEPTH).contains(depth))
+        .ok_or_else(|| format!("'{value}' is not a depth between 1 and {MAX_SUPPORTED_DEPTH}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(max_depth: usize) -> Limits {
+        Limits {
+            max_body_bytes: 1024,
+            max_depth,
+            max_batch: 2,
+        }
+    }
+
+    #[test]
+    fn depth_at_the_limit_is_accepted() {
+        assert!(limits(3).check_depth(br#"{"a":[{"b":1}]}"#).is_ok());
+        assert!(limits(1).check_depth(b"[1, 2, 3]").is_ok());
+        assert!(limits(1).check_depth(b"42").is_ok());
+    }
+
+    #[test]
+    fn depth_over_the_limit_is_rejected() {
+        let error = limits(2).check_depth(br#"{"a":[{"b":1}]}"#).unwrap_err();
+        assert_eq!(
+            error,
+            "Invalid Request: JSON nesting exceeds the limit of 2"
+        );
+    }
+
+    #[test]
+    fn sibling_containers_do_not_add_up() {
+        assert!(limits(2).check_depth(b"[[1],[2],{},[3]]").is_ok());
+    }
+
+    #[test]
+    fn brackets_inside_strings_are_ignored() {
+        assert!(limits(1).check_depth(br#"{"a":"[[[{{{"}"#).is_ok());
+        assert!(limits(1).check_depth(br#"["]]]]", "[[[["]"#).is_ok());
+    }
+
+    #[test]
+    fn escaped_quotes_do_not_end_strings() {
+        assert!(limits(1).check_depth(br#"{"a":"\"[[["}"#).is_ok());
+    }
+
+    #[test]
+    fn escaped_backslash_before_a_quote_ends_the_string() {
+        assert!(limits(1).check_depth(br#"{"a":"\\"}"#).is_ok());
+        assert!(limits(1).check_depth(br#"{"a":"\\",[1]}"#).is_err());
+    }
+
+    #[test]
+    fn batch_size_is_bounded() {
+        assert!(limits(1).check_batch(2).is_ok());
+        assert_eq!(
+            limits(1).check_batch(3).unwrap_err(),
+            "Invalid Request: batch of 3 messages exceeds the limit of 2"
+        );
+    }
+
+    #[test]
+    fn parse_depth_accepts_what_serde_json_can_parse() {
+        assert_eq!(parse_depth("1"), Ok(1));
+        assert_eq!(parse_depth("127"), Ok(MAX_SUPPORTED_DEPTH));
+        assert!(parse_depth("0").is_err());
+        assert!(parse_depth("128").is_err());
+        assert!(parse_depth("deep").is_err());
+    }
+
+    #[test]
+    fn max_supported_depth_matches_serde_json() {
+        let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(serde_json::from_str::<serde_json::Value>(&nested(MAX_SUPPORTED_DEPTH)).is_ok());
+        assert!(
+            serde_json::from_str::<serde_json::Value>(&nested(MAX_SUPPORTED_DEPTH + 1)).is_err()
+        );
+    }
+}
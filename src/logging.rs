@@ -0,0 +1,67 @@
+use crate::telemetry;
+use clap::ValueEnum;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use std::io::IsTerminal;
+use tracing::Level;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+/// Output format of the structured logs (`--log-format`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable, multi-line
+    #[default]
+    Pretty,
+    /// One JSON object per line, including the enclosing spans
+    Json,
+}
+
+/// Flushes buffered OTLP spans when dropped at the end of `main`.
+pub struct LogGuard(Option<SdkTracerProvider>);
+
+impl Drop for LogGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.0.take() {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("[ERROR] Failed to flush OTLP spans: {e}");
+            }
+        }
+    }
+}
+
+/// Installs the global subscriber. Logs go to stderr so they never mix with
+/// the JSON-RPC stream of the stdio transport. `filter` uses the `RUST_LOG`
+/// syntax; `off` disables logging entirely, which is what benchmarks want.
+///
+/// With `otlp_endpoint`, this crate's spans are also exported as traces,
+/// independently of `filter`.
+pub fn init(
+    filter: &str,
+    format: LogFormat,
+    otlp_endpoint: Option<&str>,
+) -> anyhow::Result<LogGuard> {
+    let fmt = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal());
+    let fmt = match format {
+        LogFormat::Pretty => fmt.pretty().boxed(),
+        LogFormat::Json => fmt.json().boxed(),
+    }
+    .with_filter(EnvFilter::try_new(filter)?);
+
+    let provider = otlp_endpoint.map(telemetry::tracer_provider).transpose()?;
+    let otel = provider.as_ref().map(|provider| {
+        tracing_opentelemetry::layer()
+            .with_tracer(provider.tracer(env!("CARGO_PKG_NAME")))
+            .with_filter(Targets::new().with_target(env!("CARGO_CRATE_NAME"), Level::INFO))
+    });
+
+    tracing_subscriber::registry()
+        .with(fmt)
+        .with(otel)
+        .try_init()?;
+    Ok(LogGuard(provider))
+}
@@ -1,17 +1,54 @@
+mod auth;
+mod health;
+mod limits;
+mod logging;
+mod metrics;
+mod net;
+mod protocol;
+mod ratelimit;
+mod session;
+mod shutdown;
+mod telemetry;
+mod tls;
+mod tools;
+
+use auth::Auth;
 use axum::{
     body::Body,
-    http::{header, StatusCode},
-    response::Response,
+    extract::State,
+    http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode},
+    middleware,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     routing::post,
-    Json, Router,
+    Extension, Router,
 };
-use axum_server::tls_rustls::RustlsConfig;
-use chrono::{DateTime, Utc};
+use axum_server::tls_rustls::{RustlsAcceptor, RustlsConfig};
+use axum_server::Handle;
 use clap::Parser;
+use health::Readiness;
+use limits::Limits;
+use logging::LogFormat;
+use metrics::{Metrics, TransportErrorCode};
+use net::{HttpMode, ListenAddr};
+use protocol::ProtocolVersion;
+use ratelimit::{RateLimitKey, RateLimitResponse, RateLimiter};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use session::{Session, SessionStore};
 use std::net::SocketAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tls::{ClientCertAcceptor, ClientCertSubject, TlsFiles};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, BufWriter};
+use tokio::task::JoinSet;
+use tokio_stream::{wrappers::BroadcastStream, StreamExt};
+use tools::{ToolError, ToolRegistry};
+use tracing::{field, Instrument};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 // --- Clap Argument Parsing ---
 
@@ -24,6 +61,115 @@ struct Args {
     /// Path to the TLS key file
     #[arg(long)]
     tls_key: Option<PathBuf>,
+    /// Require client certificates signed by this PEM CA bundle (mutual TLS)
+    #[arg(long, requires = "tls_cert")]
+    tls_client_ca: Option<PathBuf>,
+    /// Address to listen on (IPv4 or IPv6, optionally with a port); repeat or
+    /// comma-separate to listen on several
+    #[arg(
+        long,
+        env = "MCP_LISTEN",
+        value_delimiter = ',',
+        default_value = "0.0.0.0"
+    )]
+    listen: Vec<ListenAddr>,
+    /// Port used by --listen addresses that do not specify one
+    #[arg(long, env = "MCP_PORT", default_value_t = 3000)]
+    port: u16,
+    /// Serve HTTPS on this port of every --listen address while --port stays
+    /// plaintext HTTP (not with --tls-client-ca, which the plaintext port would bypass)
+    #[arg(long, requires = "tls_cert", conflicts_with = "tls_client_ca")]
+    tls_port: Option<u16>,
+    /// Accept this static bearer token; repeat or comma-separate for several
+    #[arg(
+        long,
+        env = "MCP_AUTH_TOKENS",
+        value_delimiter = ',',
+        hide_env_values = true
+    )]
+    auth_token: Vec<String>,
+    /// Accept HS256-signed JWT bearer tokens verified with this shared secret
+    #[arg(long, env = "MCP_JWT_SECRET", hide_env_values = true)]
+    jwt_secret: Option<String>,
+    /// Accept RS256-signed JWT bearer tokens verified with this PEM public key
+    #[arg(long, env = "MCP_JWT_PUBLIC_KEY")]
+    jwt_public_key: Option<PathBuf>,
+    /// Required JWT `aud` value; repeat to accept several (unchecked if omitted)
+    #[arg(long, env = "MCP_JWT_AUDIENCE", value_delimiter = ',')]
+    jwt_audience: Vec<String>,
+    /// Serve HTTP on this Unix domain socket path instead of TCP; --listen,
+    /// --port and their environment variables are then ignored
+    #[arg(long, conflicts_with_all = ["tls_cert", "tls_key", "tls_client_ca"])]
+    unix_socket: Option<PathBuf>,
+    /// Octal permission mode for the --unix-socket file (e.g. 660)
+    #[arg(long, requires = "unix_socket", value_parser = net::parse_mode)]
+    unix_socket_mode: Option<u32>,
+    /// HTTP versions to serve
+    #[arg(long, env = "MCP_HTTP", value_enum, default_value_t = HttpMode::Auto)]
+    http: HttpMode,
+    /// Seconds to let open connections finish after SIGINT/SIGTERM before closing them
+    #[arg(long, env = "MCP_SHUTDOWN_TIMEOUT", default_value_t = 30)]
+    shutdown_timeout: u64,
+    /// Log filter in `RUST_LOG` syntax (e.g. `info`, `mcp_time_server=debug`); `off` disables logging
+    #[arg(long, env = "RUST_LOG", default_value = "warn")]
+    log_level: String,
+    /// Log output format
+    #[arg(long, env = "MCP_LOG_FORMAT", value_enum, default_value_t = LogFormat::Pretty)]
+    log_format: LogFormat,
+    /// Export traces to this OTLP/HTTP collector URL (e.g. `http://localhost:4318/v1/traces`)
+    #[arg(long, env = "MCP_OTLP_ENDPOINT")]
+    otlp_endpoint: Option<String>,
+    /// Serve newline-delimited JSON-RPC over stdin/stdout instead of HTTP
+    #[arg(long, conflicts_with_all = ["tls_cert", "tls_key", "tls_client_ca", "unix_socket"])]
+    stdio: bool,
+    /// Largest accepted request body (or stdio line) in bytes
+    #[arg(long, env = "MCP_MAX_BODY_BYTES", default_value_t = 2 * 1024 * 1024)]
+    max_body_bytes: usize,
+    /// Deepest accepted nesting of JSON arrays and objects in a message
+    #[arg(
+        long,
+        env = "MCP_MAX_JSON_DEPTH",
+        default_value_t = 64,
+        value_parser = limits::parse_depth
+    )]
+    max_json_depth: usize,
+    /// Most messages accepted in one JSON-RPC batch
+    #[arg(long, env = "MCP_MAX_BATCH", default_value_t = 100)]
+    max_batch: usize,
+    /// Allow each client this many requests per second to /mcp (token bucket)
+    #[arg(long, env = "MCP_RATE_LIMIT")]
+    rate_limit: Option<f64>,
+    /// Requests a client may burst above --rate-limit (defaults to one second's
+    /// worth; ignored without --rate-limit)
+    #[arg(long, env = "MCP_RATE_LIMIT_BURST")]
+    rate_limit_burst: Option<u32>,
+    /// What identifies a client for --rate-limit
+    #[arg(long, env = "MCP_RATE_LIMIT_KEY", value_enum, default_value_t = RateLimitKey::Ip)]
+    rate_limit_key: RateLimitKey,
+    /// Handle at most this many /mcp requests at once, rejecting the rest
+    #[arg(long, env = "MCP_MAX_CONCURRENT_REQUESTS")]
+    max_concurrent_requests: Option<usize>,
+    /// How requests rejected by --rate-limit or --max-concurrent-requests are answered
+    #[arg(
+        long,
+        env = "MCP_RATE_LIMIT_RESPONSE",
+        value_enum,
+        default_value_t = RateLimitResponse::Status
+    )]
+    rate_limit_response: RateLimitResponse,
+    /// Enforce the session lifecycle: non-initialize requests need an Mcp-Session-Id
+    /// header, and sessions must send notifications/initialized before other requests
+    #[arg(long)]
+    require_session: bool,
+    /// Most HTTP sessions open at once; further initialize requests are refused
+    #[arg(long, env = "MCP_MAX_SESSIONS", default_value_t = 10_000)]
+    max_sessions: usize,
+    /// Seconds after which an HTTP session without requests is ended, closing its streams
+    #[arg(long, env = "MCP_SESSION_IDLE_TIMEOUT", default_value_t = 1800)]
+    session_idle_timeout: u64,
+    /// Answer POST requests as SSE streams whenever the client accepts text/event-stream
+    #[arg(long)]
+    sse_responses: bool,
 }
 
 // --- JSON-RPC Request Structures ---
@@ -36,10 +182,10 @@ enum JsonRpcRequest {
     Notification(JsonRpcNotification),
 }
 
+// The `jsonrpc` member is checked by `validate_request` before these are decoded.
+
 #[derive(Deserialize, Debug)]
 struct JsonRpcRequestWithParams {
-    #[allow(dead_code)]
-    jsonrpc: String,
     id: Value,
     method: String,
     params: Value,
@@ -47,25 +193,18 @@ struct JsonRpcRequestWithParams {
 
 #[derive(Deserialize, Debug)]
 struct JsonRpcRequestWithoutParams {
-    #[allow(dead_code)]
-    jsonrpc: String,
     id: Value,
     method: String,
 }
 
 #[derive(Deserialize, Debug)]
 struct JsonRpcNotification {
-    #[allow(dead_code)]
-    jsonrpc: String,
-    #[allow(dead_code)]
     method: String,
-    #[allow(dead_code)]
     params: Option<Value>,
 }
 
 #[derive(Deserialize, Debug)]
 struct InitializeParams {
-    #[allow(dead_code)]
     #[serde(rename = "protocolVersion")]
     protocol_version: String,
     #[allow(dead_code)]
@@ -75,6 +214,12 @@ struct InitializeParams {
     client_info: Value,
 }
 
+#[derive(Deserialize, Debug)]
+struct CancelledParams {
+    #[serde(rename = "requestId")]
+    request_id: Value,
+}
+
 #[derive(Deserialize, Debug)]
 struct ToolCallParams {
     name: String,
@@ -131,134 +276,672 @@ fn create_jsonrpc_response(json_response: &serde_json::Value) -> Response {
         .unwrap()
 }
 
-// --- Axum Handler ---
-#[allow(clippy::unused_async)]
-async fn mcp_handler(Json(request_value): Json<Value>) -> Response {
-    let request: Result<JsonRpcRequest, _> = serde_json::from_value(request_value.clone());
-
-    match request {
-        Ok(JsonRpcRequest::WithParams(req)) => handle_request_with_params(req).await,
-        Ok(JsonRpcRequest::WithoutParams(req)) => handle_request_without_params(req).await,
-        Ok(JsonRpcRequest::Notification(req)) => handle_notification(req).await,
-        Err(_) => {
+// --- Dispatch ---
+
+/// Checks the JSON-RPC 2.0 request envelope: an object with `"jsonrpc": "2.0"`,
+/// a string `method`, an optional string/number/null `id` and optional
+/// structured `params`.
+fn validate_request(request_value: &Value) -> Result<(), &'static str> {
+    let Some(request) = request_value.as_object() else {
+        return Err("Invalid Request: expected a JSON object");
+    };
+    if request.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+        return Err("Invalid Request: jsonrpc must be \"2.0\"");
+    }
+    match request.get("method") {
+        Some(Value::String(_)) => {}
+        Some(_) => return Err("Invalid Request: method must be a string"),
+        None => return Err("Invalid Request: missing method"),
+    }
+    if !matches!(
+        request.get("id"),
+        None | Some(Value::String(_) | Value::Number(_) | Value::Null)
+    ) {
+        return Err("Invalid Request: id must be a string, number or null");
+    }
+    if !matches!(
+        request.get("params"),
+        None | Some(Value::Object(_) | Value::Array(_))
+    ) {
+        return Err("Invalid Request: params must be an object or array");
+    }
+    Ok(())
+}
+
+/// Per-message context supplied by the transport.
+#[derive(Clone, Copy)]
+struct DispatchContext<'a> {
+    tools: &'a ToolRegistry,
+    session: Option<&'a Session>,
+    /// Revision announced by the transport itself (the HTTP
+    /// `MCP-Protocol-Version` header), used when the session has none.
+    header_version: Option<ProtocolVersion>,
+    /// Refuse requests other than `ping` until the session has received
+    /// `notifications/initialized`.
+    enforce_lifecycle: bool,
+    /// Register requests with the session so `notifications/cancelled` can
+    /// reach them. Off for stdio, which handles one message at a time, so a
+    /// cancellation always arrives after the response has been written.
+    track_cancellation: bool,
+    limits: Limits,
+    metrics: Option<&'a Metrics>,
+}
+
+impl DispatchContext<'_> {
+    /// Revision governing this message: negotiated on the session, else
+    /// announced by the transport, else the spec fallback.
+    fn protocol_version(&self) -> ProtocolVersion {
+        self.session
+            .and_then(Session::protocol_version)
+            .or(self.header_version)
+            .unwrap_or(ProtocolVersion::FALLBACK)
+    }
+}
+
+/// Routes a decoded JSON-RPC message to its handler. Returns `None` for
+/// notifications, which never produce a response message.
+///
+/// Each message is handled in its own `call` span, which records method, id,
+/// tool, latency and error code, and is counted in the metrics.
+async fn dispatch(request_value: Value, ctx: DispatchContext<'_>) -> Option<Value> {
+    let started = Instant::now();
+    let method = request_value
+        .get("method")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let tool = request_value
+        .pointer("/params/name")
+        .and_then(Value::as_str)
+        .filter(|_| method.as_deref() == Some("tools/call"))
+        .and_then(|name| ctx.tools.get(name))
+        .map(tools::Tool::name);
+    let id = request_value.get("id").map(|id| match id {
+        Value::String(id) => id.clone(),
+        id => id.to_string(),
+    });
+    let span = tracing::info_span!(
+        "call",
+        otel.name = method.as_deref().unwrap_or("call"),
+        otel.status_code = field::Empty,
+        method = method.as_deref(),
+        id = id.as_deref(),
+        tool,
+        latency_us = field::Empty,
+        error_code = field::Empty,
+    );
+    // A parent in `_meta` overrides the HTTP-level one for this message.
+    if let Some(parent) = request_value
+        .pointer("/params/_meta")
+        .and_then(telemetry::from_meta)
+    {
+        let _ = span.set_parent(parent);
+    }
+
+    let response = route_message(request_value, ctx)
+        .instrument(span.clone())
+        .await;
+
+    let elapsed = started.elapsed();
+    let error_code = response
+        .as_ref()
+        .and_then(|response| response.pointer("/error/code"))
+        .and_then(Value::as_i64);
+    span.record(
+        "latency_us",
+        u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX),
+    );
+    span.record("error_code", error_code);
+    if error_code.is_some() {
+        span.record("otel.status_code", "ERROR");
+    }
+    // Failures repeat the call fields, since at the default `warn` level the
+    // INFO span is disabled and would not contribute them.
+    span.in_scope(|| {
+        if error_code.is_some() {
+            tracing::warn!(
+                method = method.as_deref(),
+                id = id.as_deref(),
+                tool,
+                error_code,
+                "call failed"
+            );
+        } else {
+            tracing::info!("call completed");
+        }
+    });
+    if let Some(metrics) = ctx.metrics {
+        let method = Metrics::method_label(method.as_deref());
+        metrics.observe_message(method, tool, response.as_ref(), elapsed);
+    }
+    response
+}
+
+async fn route_message(request_value: Value, ctx: DispatchContext<'_>) -> Option<Value> {
+    // Only echo ids of a valid type; anything else is reported as null.
+    let error_id = match request_value.get("id") {
+        Some(id @ (Value::String(_) | Value::Number(_))) => id.clone(),
+        _ => Value::Null,
+    };
+    // Decoded from the owned value so params are moved, not copied.
+    let request = validate_request(&request_value)
+        .and_then(|()| JsonRpcRequest::deserialize(request_value).map_err(|_| "Invalid Request"));
+
+    let (key, mut response) = match request {
+        Ok(JsonRpcRequest::WithParams(req)) => {
+            if let Some(error) = check_lifecycle(&req.id, &req.method, ctx) {
+                return Some(error);
+            }
+            let key = track_request(&req.id, ctx);
+            (key, handle_request_with_params(req, ctx).await)
+        }
+        Ok(JsonRpcRequest::WithoutParams(req)) => {
+            if let Some(error) = check_lifecycle(&req.id, &req.method, ctx) {
+                return Some(error);
+            }
+            let key = track_request(&req.id, ctx);
+            (key, handle_request_without_params(req, ctx).await)
+        }
+        Ok(JsonRpcRequest::Notification(req)) => {
+            handle_notification(req, ctx).await;
+            return None;
+        }
+        Err(message) => {
+            let error = JsonRpcErrorResponse::new(error_id, -32600, message.to_string());
+            return Some(serde_json::to_value(error).unwrap());
+        }
+    };
+
+    match (ctx.session, key) {
+        // Only HTTP tracks cancellation, and its POST still has to be
+        // answered, so the result is replaced by a server error.
+        (Some(session), Some(key)) if session.finish_request(&key) => {
+            let id = response["id"].take();
+            let error = JsonRpcErrorResponse::new(id, -32000, "Request cancelled".to_string());
+            Some(serde_json::to_value(error).unwrap())
+        }
+        _ => Some(response),
+    }
+}
+
+fn check_lifecycle(id: &Value, method: &str, ctx: DispatchContext<'_>) -> Option<Value> {
+    let session = ctx.session?;
+    if !ctx.enforce_lifecycle || session.is_initialized() || matches!(method, "initialize" | "ping")
+    {
+        return None;
+    }
+    let error = JsonRpcErrorResponse::new(
+        id.clone(),
+        -32600,
+        "Invalid Request: session has not sent notifications/initialized".to_string(),
+    );
+    Some(serde_json::to_value(error).unwrap())
+}
+
+// Registers the request with its session so a later notifications/cancelled can find it.
+fn track_request(id: &Value, ctx: DispatchContext<'_>) -> Option<String> {
+    let session = ctx.session.filter(|_| ctx.track_cancellation)?;
+    Some(session.begin_request(id))
+}
+
+/// Entry point for a decoded message: a single request or notification, or a
+/// JSON-RPC batch. A batch yields an array of the responses to its requests, or
+/// `None` when it held only notifications; revisions from 2025-06-18 on reject
+/// batches outright.
+async fn dispatch_message(message: Value, ctx: DispatchContext<'_>) -> Option<Value> {
+    let Value::Array(batch) = message else {
+        return dispatch(message, ctx).await;
+    };
+    let version = ctx.protocol_version();
+    if !version.supports_batching() {
+        let message = format!(
+            "Invalid Request: batches are not supported in protocol version {}",
+            version.as_str()
+        );
+        return Some(batch_error(Value::Null, message, ctx));
+    }
+    if batch.is_empty() {
+        return Some(batch_error(Value::Null, "Invalid Request".to_string(), ctx));
+    }
+    if let Err(message) = ctx.limits.check_batch(batch.len()) {
+        return Some(batch_error(Value::Null, message, ctx));
+    }
+
+    let mut responses = Vec::with_capacity(batch.len());
+    for request_value in batch {
+        // The session is issued from a standalone initialize request, so it
+        // cannot be batched with other calls.
+        if request_value.get("method").and_then(Value::as_str) == Some("initialize") {
             let id = request_value.get("id").cloned().unwrap_or(Value::Null);
-            let error = JsonRpcErrorResponse::new(id, -32700, "Parse error".to_string());
-            create_jsonrpc_response(&serde_json::to_value(error).unwrap())
+            let message = "Invalid Request: initialize must not be part of a batch".to_string();
+            responses.push(batch_error(id, message, ctx));
+        } else if let Some(response) = dispatch(request_value, ctx).await {
+            responses.push(response);
         }
     }
+    if responses.is_empty() {
+        None
+    } else {
+        Some(Value::Array(responses))
+    }
 }
-#[allow(clippy::unused_async)]
-async fn handle_request_with_params(req: JsonRpcRequestWithParams) -> Response {
-    match req.method.as_str() {
-        "initialize" => process_init(&req),
-        "tools/list" => {
-            // tools/list can be called with or without params
-            let tools = serde_json::json!([
-                {
-                    "name": "get_system_time",
-                    "description": "Get current system time in specified timezone",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "timezone": {
-                                "type": "string",
-                                "description": "IANA timezone name (e.g., 'America/New_York', 'Europe/London'). Defaults to UTC"
-                            }
-                        }
-                    },
-                    "annotations": {
-                        "title": "Get System Time",
-                        "readOnlyHint": true,
-                        "destructiveHint": false,
-                        "idempotentHint": false,
-                        "openWorldHint": false
-                    }
-                }
-            ]);
-            let response = JsonRpcResponse {
-                jsonrpc: "2.0".to_string(),
-                id: req.id,
-                result: serde_json::json!({ "tools": tools }),
-            };
-            create_jsonrpc_response(&serde_json::to_value(response).unwrap())
+
+// Batch-level rejections never reach `dispatch`, so they are counted here.
+fn batch_error(id: Value, message: String, ctx: DispatchContext<'_>) -> Value {
+    if let Some(metrics) = ctx.metrics {
+        metrics.observe_error(-32600);
+    }
+    serde_json::to_value(JsonRpcErrorResponse::new(id, -32600, message)).unwrap()
+}
+
+// --- Axum Handlers ---
+
+#[derive(Clone)]
+struct AppState {
+    tools: Arc<ToolRegistry>,
+    sessions: SessionStore,
+    require_session: bool,
+    sse_responses: bool,
+    limits: Limits,
+    metrics: Arc<Metrics>,
+}
+
+const MCP_SESSION_ID: HeaderName = HeaderName::from_static("mcp-session-id");
+const MCP_PROTOCOL_VERSION: HeaderName = HeaderName::from_static("mcp-protocol-version");
+
+fn accepts(headers: &HeaderMap, mime: &str) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .any(|value| value.contains(mime))
+}
+
+fn session_id(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(MCP_SESSION_ID)
+        .and_then(|value| value.to_str().ok())
+}
+
+// Transport-level failures carry no request id, so they are reported with `id: null`.
+fn create_transport_error(status: StatusCode, code: i32, message: &str) -> Response {
+    let error = JsonRpcErrorResponse::new(Value::Null, code, message.to_string());
+    let mut response = create_jsonrpc_response(&serde_json::to_value(error).unwrap());
+    *response.status_mut() = status;
+    response.extensions_mut().insert(TransportErrorCode(code));
+    response
+}
+
+enum SessionError {
+    Missing,
+    Unknown,
+}
+
+impl IntoResponse for SessionError {
+    fn into_response(self) -> Response {
+        match self {
+            SessionError::Missing => create_transport_error(
+                StatusCode::BAD_REQUEST,
+                -32000,
+                "Bad Request: Mcp-Session-Id header is required",
+            ),
+            SessionError::Unknown => {
+                create_transport_error(StatusCode::NOT_FOUND, -32001, "Session not found")
+            }
         }
-        "tools/call" => {
-            let params: Result<ToolCallParams, _> = serde_json::from_value(req.params);
-            if let Ok(tool_params) = params {
-                if tool_params.name == "get_system_time" {
-                    #[derive(Deserialize, Debug)]
-                    struct ToolArguments {
-                        #[serde(default)]
-                        timezone: String,
-                    }
+    }
+}
 
-                    let args: Result<ToolArguments, _> =
-                        serde_json::from_value(tool_params.arguments);
-                    if let Ok(args) = args {
-                        if !args.timezone.is_empty() && args.timezone.to_uppercase() != "UTC" {
-                            let error = JsonRpcErrorResponse::new(
-                                req.id.clone(),
-                                -32602,
-                                "Invalid params: only 'UTC' timezone is supported".to_string(),
-                            );
-                            return create_jsonrpc_response(&serde_json::to_value(error).unwrap());
-                        }
+/// Looks up a session, treating one opened under a different client
+/// certificate as unknown so ids cannot be replayed across mTLS identities.
+fn find_session(state: &AppState, id: &str, client: Option<&str>) -> Option<Arc<Session>> {
+    state
+        .sessions
+        .get(id)
+        .filter(|session| session.client() == client)
+}
 
-                        let now: DateTime<Utc> = Utc::now();
-                        let time_str = now.format("%Y-%m-%dT%H:%M:%SZ").to_string();
-
-                        let response = JsonRpcResponse {
-                            jsonrpc: "2.0".to_string(),
-                            id: req.id,
-                            result: serde_json::json!({
-                                "content": [
-                                    {
-                                        "type": "text",
-                                        "text": time_str
-                                    }
-                                ],
-                                "isError": false
-                            }),
-                        };
-                        create_jsonrpc_response(&serde_json::to_value(response).unwrap())
-                    } else {
-                        let error = JsonRpcErrorResponse::new(
-                            req.id,
-                            -32602,
-                            "Invalid params for get_system_time".to_string(),
-                        );
-                        create_jsonrpc_response(&serde_json::to_value(error).unwrap())
-                    }
-                } else {
-                    let error =
-                        JsonRpcErrorResponse::new(req.id, -32601, "Method not found".to_string());
-                    create_jsonrpc_response(&serde_json::to_value(error).unwrap())
-                }
-            } else {
+/// Checks the `Mcp-Session-Id` header against the store. A missing header is
+/// accepted unless `--require-session` is set, so sessionless load generators keep working.
+fn validate_session(
+    state: &AppState,
+    headers: &HeaderMap,
+    client: Option<&str>,
+) -> Result<Option<Arc<Session>>, SessionError> {
+    match session_id(headers) {
+        Some(id) => find_session(state, id, client)
+            .map(Some)
+            .ok_or(SessionError::Unknown),
+        None if state.require_session => Err(SessionError::Missing),
+        None => Ok(None),
+    }
+}
+
+fn client_subject(client: Option<&Extension<ClientCertSubject>>) -> Option<&str> {
+    client.map(|Extension(ClientCertSubject(subject))| subject.as_str())
+}
+
+// Helper function to wrap a JSON-RPC message in a single-event SSE stream
+fn create_sse_response(json_response: &serde_json::Value) -> Response {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/event-stream")
+        .header(header::CACHE_CONTROL, "no-cache")
+        .body(Body::from(format!(
+            "event: message\ndata: {}\n\n",
+            serde_json::to_string(&json_response).unwrap()
+        )))
+        .unwrap()
+}
+
+async fn mcp_handler(
+    State(state): State<AppState>,
+    client: Option<Extension<ClientCertSubject>>,
+    headers: HeaderMap,
+    body: Body,
+) -> Response {
+    let client = client_subject(client.as_ref());
+    // Read and decoded by hand rather than with axum's `Bytes`/`Json`
+    // extractors so oversized and malformed bodies get JSON-RPC errors instead
+    // of plain-text rejections.
+    let Ok(body) = axum::body::to_bytes(body, state.limits.max_body_bytes).await else {
+        return create_transport_error(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            -32600,
+            &state.limits.body_too_large_message(),
+        );
+    };
+    if let Err(message) = state.limits.check_depth(&body) {
+        return create_transport_error(StatusCode::BAD_REQUEST, -32600, &message);
+    }
+    let Ok(request_value) = serde_json::from_slice::<Value>(&body) else {
+        return create_transport_error(StatusCode::BAD_REQUEST, -32700, "Parse error");
+    };
+    let header_version = match headers.get(MCP_PROTOCOL_VERSION) {
+        Some(value) => match value.to_str().ok().and_then(ProtocolVersion::parse) {
+            Some(version) => Some(version),
+            None => {
+                return create_transport_error(
+                    StatusCode::BAD_REQUEST,
+                    -32000,
+                    "Bad Request: unsupported MCP-Protocol-Version",
+                )
+            }
+        },
+        None => None,
+    };
+    let is_initialize = request_value.get("method").and_then(Value::as_str) == Some("initialize");
+    // initialize starts a fresh session; it is only stored once the request succeeds.
+    let session = if is_initialize {
+        Some(Arc::new(Session::for_client(client.map(str::to_string))))
+    } else {
+        match validate_session(&state, &headers, client) {
+            Ok(session) => session,
+            Err(error) => return error.into_response(),
+        }
+    };
+    let ctx = DispatchContext {
+        tools: &state.tools,
+        session: session.as_deref(),
+        header_version,
+        enforce_lifecycle: state.require_session,
+        track_cancellation: true,
+        limits: state.limits,
+        metrics: Some(&state.metrics),
+    };
+
+    // Parent of the per-call spans: ties them to the session and the mTLS client.
+    let span = tracing::info_span!(
+        "mcp",
+        otel.kind = "server",
+        session = session_id(&headers),
+        client
+    );
+    if let Some(parent) = telemetry::from_headers(&headers) {
+        let _ = span.set_parent(parent);
+    }
+    let Some(response) = dispatch_message(request_value, ctx).instrument(span).await else {
+        // Notifications (and batches of only notifications) have no response
+        // message; the Streamable HTTP transport acknowledges them with 202.
+        return StatusCode::ACCEPTED.into_response();
+    };
+
+    let use_sse = accepts(&headers, "text/event-stream")
+        && (state.sse_responses || !accepts(&headers, "application/json"));
+    let mut http_response = if use_sse {
+        create_sse_response(&response)
+    } else {
+        create_jsonrpc_response(&response)
+    };
+    let initialized = is_initialize && response.get("result").is_some();
+    if let Some(session) = session.filter(|_| initialized) {
+        let Some(id) = state.sessions.insert(session) else {
+            return create_transport_error(
+                StatusCode::SERVICE_UNAVAILABLE,
+                -32000,
+                "Service Unavailable: session limit reached",
+            );
+        };
+        http_response
+            .headers_mut()
+            .insert(MCP_SESSION_ID, HeaderValue::from_str(&id).unwrap());
+    }
+    http_response
+}
+
+/// GET `/mcp`: opens the SSE stream for server-initiated messages of a session.
+/// The server has none to send yet, so the stream only carries keep-alives
+/// until the session ends.
+async fn sse_handler(
+    State(state): State<AppState>,
+    client: Option<Extension<ClientCertSubject>>,
+    headers: HeaderMap,
+) -> Response {
+    if !accepts(&headers, "text/event-stream") {
+        return StatusCode::NOT_ACCEPTABLE.into_response();
+    }
+    let Some(id) = session_id(&headers) else {
+        return SessionError::Missing.into_response();
+    };
+    let Some(session) = find_session(&state, id, client_subject(client.as_ref())) else {
+        return SessionError::Unknown.into_response();
+    };
+
+    // Lagged receivers skip the dropped messages rather than ending the stream.
+    let stream = BroadcastStream::new(session.subscribe()).filter_map(|message| {
+        message
+            .ok()
+            .map(|message| Event::default().event("message").json_data(message))
+    });
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// DELETE `/mcp`: terminates the session named by `Mcp-Session-Id`.
+async fn delete_session_handler(
+    State(state): State<AppState>,
+    client: Option<Extension<ClientCertSubject>>,
+    headers: HeaderMap,
+) -> Response {
+    let client = client_subject(client.as_ref());
+    match session_id(&headers) {
+        Some(id) if find_session(&state, id, client).is_some() && state.sessions.remove(id) => {
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Some(_) => SessionError::Unknown.into_response(),
+        None => SessionError::Missing.into_response(),
+    }
+}
+
+// --- Stdio Transport ---
+
+/// Serves newline-delimited JSON-RPC over stdin/stdout until stdin closes.
+/// Responses are written in request order; stdout is flushed once the
+/// already-buffered input has been drained so pipelined clients get batched writes.
+async fn serve_stdio(tools: &ToolRegistry, limits: Limits) -> std::io::Result<()> {
+    let mut reader = BufReader::new(tokio::io::stdin());
+    let mut writer = BufWriter::new(tokio::io::stdout());
+    let mut line = Vec::new();
+    // One byte past the limit is enough to tell that a line is oversized.
+    let read_limit = limits.max_body_bytes.saturating_add(1);
+    // The whole stdio connection is a single session.
+    let session = Session::default();
+    let ctx = DispatchContext {
+        tools,
+        session: Some(&session),
+        header_version: None,
+        enforce_lifecycle: false,
+        track_cancellation: false,
+        limits,
+        metrics: None,
+    };
+
+    loop {
+        line.clear();
+        let read = (&mut reader)
+            .take(read_limit as u64)
+            .read_until(b'\n', &mut line)
+            .await?;
+        if read == 0 {
+            break;
+        }
+        let oversized = line.len() == read_limit && line.last() != Some(&b'\n');
+        if oversized {
+            skip_line(&mut reader).await?;
+        }
+        let message = line.trim_ascii();
+        if message.is_empty() {
+            continue;
+        }
+
+        let checked = if oversized || message.len() > limits.max_body_bytes {
+            Err((-32600, limits.body_too_large_message()))
+        } else {
+            limits
+                .check_depth(message)
+                .map_err(|message| (-32600, message))
+                .and_then(|()| {
+                    serde_json::from_slice::<Value>(message)
+                        .map_err(|_| (-32700, "Parse error".to_string()))
+                })
+        };
+        let response = match checked {
+            Ok(request_value) => dispatch_message(request_value, ctx).await,
+            Err((code, message)) => {
+                let error = JsonRpcErrorResponse::new(Value::Null, code, message);
+                Some(serde_json::to_value(error).unwrap())
+            }
+        };
+        if let Some(response) = response {
+            let mut out = serde_json::to_vec(&response).unwrap();
+            out.push(b'\n');
+            writer.write_all(&out).await?;
+        }
+        if reader.buffer().is_empty() {
+            writer.flush().await?;
+        }
+    }
+    writer.flush().await
+}
+
+/// Consumes input up to and including the next newline without keeping it.
+async fn skip_line<R: AsyncBufRead + Unpin>(reader: &mut R) -> std::io::Result<()> {
+    loop {
+        let available = reader.fill_buf().await?;
+        if available.is_empty() {
+            return Ok(());
+        }
+        if let Some(end) = available.iter().position(|&byte| byte == b'\n') {
+            reader.consume(end + 1);
+            return Ok(());
+        }
+        let len = available.len();
+        reader.consume(len);
+    }
+}
+
+async fn handle_request_with_params(
+    req: JsonRpcRequestWithParams,
+    ctx: DispatchContext<'_>,
+) -> Value {
+    match req.method.as_str() {
+        "initialize" => process_init(req, ctx),
+        "ping" => process_ping(req.id),
+        // tools/list can be called with or without params
+        "tools/list" => process_tools_list(req.id, ctx),
+        "tools/call" => {
+            let params: Result<ToolCallParams, _> = serde_json::from_value(req.params);
+            let Ok(tool_params) = params else {
                 let error = JsonRpcErrorResponse::new(
                     req.id,
                     -32602,
                     "Invalid params for tools/call".to_string(),
                 );
-                create_jsonrpc_response(&serde_json::to_value(error).unwrap())
+                return serde_json::to_value(error).unwrap();
+            };
+            let Some(tool) = ctx.tools.get(&tool_params.name) else {
+                let error =
+                    JsonRpcErrorResponse::new(req.id, -32601, "Method not found".to_string());
+                return serde_json::to_value(error).unwrap();
+            };
+            match tool.call(tool_params.arguments).await {
+                Ok(mut result) => {
+                    if !ctx.protocol_version().supports_structured_output() {
+                        if let Some(result) = result.as_object_mut() {
+                            result.remove("structuredContent");
+                        }
+                    }
+                    let response = JsonRpcResponse {
+                        jsonrpc: "2.0".to_string(),
+                        id: req.id,
+                        result,
+                    };
+                    serde_json::to_value(response).unwrap()
+                }
+                Err(ToolError::InvalidParams(message)) => {
+                    let error = JsonRpcErrorResponse::new(req.id, -32602, message);
+                    serde_json::to_value(error).unwrap()
+                }
             }
         }
         _ => {
             let error = JsonRpcErrorResponse::new(req.id, -32601, "Method not found".to_string());
-            create_jsonrpc_response(&serde_json::to_value(error).unwrap())
+            serde_json::to_value(error).unwrap()
         }
     }
 }
 
-fn process_init(req: &JsonRpcRequestWithParams) -> Response {
-    let params: Result<InitializeParams, _> = serde_json::from_value(req.params.clone());
+fn process_tools_list(id: Value, ctx: DispatchContext<'_>) -> Value {
+    let response = JsonRpcResponse {
+        jsonrpc: "2.0".to_string(),
+        id,
+        result: serde_json::json!({ "tools": ctx.tools.list(ctx.protocol_version()) }),
+    };
+    serde_json::to_value(response).unwrap()
+}
+
+fn process_ping(id: Value) -> Value {
+    let response = JsonRpcResponse {
+        jsonrpc: "2.0".to_string(),
+        id,
+        result: serde_json::json!({}),
+    };
+    serde_json::to_value(response).unwrap()
+}
+
+fn process_init(req: JsonRpcRequestWithParams, ctx: DispatchContext<'_>) -> Value {
+    let params: Result<InitializeParams, _> = serde_json::from_value(req.params);
     match params {
-        Ok(_params) => {
+        Ok(params) => {
+            let version = ProtocolVersion::negotiate(&params.protocol_version);
+            if let Some(session) = ctx.session {
+                session.set_protocol_version(version);
+            }
             let response = JsonRpcResponse {
                 jsonrpc: "2.0".to_string(),
-                id: req.id.clone(),
+                id: req.id,
                 result: serde_json::json!({
-                    "protocolVersion": "2024-11-05",
+                    "protocolVersion": version.as_str(),
                     "capabilities": {
                         "tools": {
                             "listChanged": false
@@ -270,64 +953,48 @@ fn process_init(req: &JsonRpcRequestWithParams) -> Response {
                     }
                 }),
             };
-            create_jsonrpc_response(&serde_json::to_value(response).unwrap())
+            serde_json::to_value(response).unwrap()
         }
         Err(e) => {
             let error = JsonRpcErrorResponse::new(
-                req.id.clone(),
+                req.id,
                 -32602,
                 format!("Invalid params for initialize: {e}"),
             );
-            create_jsonrpc_response(&serde_json::to_value(error).unwrap())
+            serde_json::to_value(error).unwrap()
         }
     }
 }
 
 #[allow(clippy::unused_async)]
-async fn handle_request_without_params(req: JsonRpcRequestWithoutParams) -> Response {
-    if req.method.as_str() == "tools/list" {
-        let tools = serde_json::json!([
-            {
-                "name": "get_system_time",
-                "description": "Get current system time in specified timezone",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "timezone": {
-                            "type": "string",
-                            "description": "IANA timezone name (e.g., 'America/New_York', 'Europe/London'). Defaults to UTC"
-                        }
-                    }
-                },
-                "annotations": {
-                    "title": "Get System Time",
-                    "readOnlyHint": true,
-                    "destructiveHint": false,
-                    "idempotentHint": false,
-                    "openWorldHint": false
-                }
-            }
-        ]);
-        let response = JsonRpcResponse {
-            jsonrpc: "2.0".to_string(),
-            id: req.id,
-            result: serde_json::json!({ "tools": tools }),
-        };
-        create_jsonrpc_response(&serde_json::to_value(response).unwrap())
+async fn handle_request_without_params(
+    req: JsonRpcRequestWithoutParams,
+    ctx: DispatchContext<'_>,
+) -> Value {
+    if req.method.as_str() == "ping" {
+        process_ping(req.id)
+    } else if req.method.as_str() == "tools/list" {
+        process_tools_list(req.id, ctx)
     } else {
         let error = JsonRpcErrorResponse::new(req.id, -32601, "Method not found".to_string());
-        create_jsonrpc_response(&serde_json::to_value(error).unwrap())
+        serde_json::to_value(error).unwrap()
     }
 }
 #[allow(clippy::unused_async)]
-async fn handle_notification(_req: JsonRpcNotification) -> Response {
-    // Notifications don't require a response, but we return 200 OK with empty body
-    // to satisfy HTTP transport requirements
-    Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, "application/json")
-        .body(Body::from("{}"))
-        .unwrap()
+async fn handle_notification(req: JsonRpcNotification, ctx: DispatchContext<'_>) {
+    let Some(session) = ctx.session else {
+        return;
+    };
+    match req.method.as_str() {
+        "notifications/initialized" => session.mark_initialized(),
+        "notifications/cancelled" => {
+            let params = req.params.map(serde_json::from_value::<CancelledParams>);
+            if let Some(Ok(params)) = params {
+                session.cancel_request(&params.request_id);
+            }
+        }
+        _ => {}
+    }
 }
 
 // --- Main Function ---
@@ -335,46 +1002,144 @@ async fn handle_notification(_req: JsonRpcNotification) -> Response {
 #[tokio::main]
 async fn main() {
     let args = Args::parse();
+    let _log_guard = logging::init(
+        &args.log_level,
+        args.log_format,
+        args.otlp_endpoint.as_deref(),
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("[ERROR] Failed to configure logging: {e}");
+        std::process::exit(1);
+    });
+    let tools = ToolRegistry::with_builtin_tools();
+    let limits = Limits {
+        max_body_bytes: args.max_body_bytes,
+        max_depth: args.max_json_depth,
+        max_batch: args.max_batch,
+    };
 
-    // Build our application with routes for both /mcp and /mcp/
-    // This ensures compatibility with wrapper.py which adds trailing slashes
-    let app = Router::new()
-        .route("/mcp", post(mcp_handler))
-        .route("/mcp/", post(mcp_handler));
-
-    // Run our app with hyper on localhost:3000
-    let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
-
-    match (args.tls_cert, args.tls_key) {
-        (Some(cert_path), Some(key_path)) => {
-            println!("MCP server listening on https://{addr}");
-            let config = RustlsConfig::from_pem_file(cert_path, key_path)
-                .await
-                .unwrap_or_else(|e| {
-                    eprintln!("[ERROR] Failed to load TLS certificate/key: {e}");
-                    std::process::exit(1);
-                });
-            axum_server::bind_rustls(addr, config)
-                .serve(app.into_make_service())
-                .await
-                .unwrap_or_else(|e| {
-                    eprintln!("[ERROR] Failed to start HTTPS server: {e}");
-                    std::process::exit(1);
-                });
-        }
-        (None, None) => {
-            println!("MCP server listening on http://{addr}");
-            let listener = tokio::net::TcpListener::bind(addr)
-                .await
-                .unwrap_or_else(|e| {
-                    eprintln!("[ERROR] Failed to bind to address {addr}: {e}");
-                    std::process::exit(1);
-                });
-            axum::serve(listener, app).await.unwrap_or_else(|e| {
-                eprintln!("[ERROR] Failed to start HTTP server: {e}");
-                std::process::exit(1);
-            });
+    if args.stdio {
+        serve_stdio(&tools, limits).await.unwrap_or_else(|e| {
+            eprintln!("[ERROR] Stdio transport failed: {e}");
+            std::process::exit(1);
+        });
+        return;
+    }
+
+    let tls = tls_files(&args);
+    let state = AppState {
+        tools: Arc::new(tools),
+        sessions: SessionStore::new(
+            args.max_sessions,
+            Duration::from_secs(args.session_idle_timeout),
+        ),
+        require_session: args.require_session,
+        sse_responses: args.sse_responses,
+        limits,
+        metrics: Arc::new(Metrics::default()),
+    };
+    let sessions = state.sessions.clone();
+    tokio::spawn(sessions.clone().expire_idle());
+    let shutdown_timeout = Duration::from_secs(args.shutdown_timeout);
+    let readiness = Readiness::default();
+    let app = build_router(state, &args, readiness.clone());
+
+    if let Some(path) = &args.unix_socket {
+        serve_unix(app, &args, path, sessions, readiness).await;
+        return;
+    }
+
+    let (plain_addrs, tls_addrs) = listen_addrs(&args, tls.is_some());
+    let bind = |addr: SocketAddr| {
+        net::bind_tcp(addr).unwrap_or_else(|e| {
+            eprintln!("[ERROR] Failed to bind to address {addr}: {e}");
+            std::process::exit(1);
+        })
+    };
+    let plain_listeners: Vec<_> = plain_addrs.into_iter().map(bind).collect();
+    let tls_listeners: Vec<_> = tls_addrs.into_iter().map(bind).collect();
+
+    let handle = Handle::new();
+    let mut servers = JoinSet::new();
+    // The peer address is what `--rate-limit-key ip` keys on.
+    let make_service = app.into_make_service_with_connect_info::<SocketAddr>();
+    if let Some(tls) = tls {
+        let server_config = tls.load().unwrap_or_else(|e| {
+            eprintln!("[ERROR] Failed to load TLS certificate/key: {e}");
+            std::process::exit(1);
+        });
+        let config = RustlsConfig::from_config(Arc::new(server_config));
+        tokio::spawn(tls::reload_on_change(tls.clone(), config.clone()));
+        for listener in tls_listeners {
+            let addr = listener.local_addr().unwrap();
+            let server = args
+                .http
+                .configure(axum_server::from_tcp(listener).unwrap())
+                .handle(handle.clone());
+            if tls.requires_client_cert() {
+                println!("MCP server listening on https://{addr} (client certificates required)");
+                let server = server.acceptor(ClientCertAcceptor::new(config.clone()));
+                servers.spawn(server.serve(make_service.clone()));
+            } else {
+                println!("MCP server listening on https://{addr}");
+                let server = server.acceptor(RustlsAcceptor::new(config.clone()));
+                servers.spawn(server.serve(make_service.clone()));
+            }
         }
+    }
+    for listener in plain_listeners {
+        println!(
+            "MCP server listening on http://{}",
+            listener.local_addr().unwrap()
+        );
+        let server = args
+            .http
+            .configure(axum_server::from_tcp(listener).unwrap())
+            .handle(handle.clone());
+        servers.spawn(server.serve(make_service.clone()));
+    }
+
+    shutdown::run_until_signal(servers, handle, sessions, readiness, shutdown_timeout).await;
+}
+
+/// Serves `app` on a Unix domain socket until shutdown, then removes the
+/// socket file.
+async fn serve_unix(
+    app: Router,
+    args: &Args,
+    path: &Path,
+    sessions: SessionStore,
+    readiness: Readiness,
+) {
+    let listener = net::bind_unix(path, args.unix_socket_mode).unwrap_or_else(|e| {
+        eprintln!(
+            "[ERROR] Failed to bind to Unix socket {}: {e}",
+            path.display()
+        );
+        std::process::exit(1);
+    });
+    println!("MCP server listening on unix:{}", path.display());
+    let handle = Handle::new();
+    let server = args
+        .http
+        .configure(axum_server::from_unix(listener).unwrap())
+        .handle(handle.clone());
+    let mut servers = JoinSet::new();
+    servers.spawn(server.serve(app.into_make_service()));
+    let timeout = Duration::from_secs(args.shutdown_timeout);
+    shutdown::run_until_signal(servers, handle, sessions, readiness, timeout).await;
+    let _ = std::fs::remove_file(path);
+}
+
+fn tls_files(args: &Args) -> Option<TlsFiles> {
+    match (&args.tls_cert, &args.tls_key) {
+        (Some(cert), Some(key)) => Some(TlsFiles {
+            cert: cert.clone(),
+            key: key.clone(),
+            client_ca: args.tls_client_ca.clone(),
+            http_mode: args.http,
+        }),
+        (None, None) => None,
         _ => {
             eprintln!(
                 "[ERROR] Both --tls-cert and --tls-key must be provided together to enable TLS."
@@ -383,3 +1148,83 @@ async fn main() {
         }
     }
 }
+
+/// Splits the `--listen` addresses into plaintext and TLS ones. With
+/// `--tls-port`, HTTPS runs next to plaintext HTTP; otherwise TLS (if
+/// configured) takes over the `--listen` addresses.
+fn listen_addrs(args: &Args, tls: bool) -> (Vec<SocketAddr>, Vec<SocketAddr>) {
+    let listen_addrs: Vec<SocketAddr> = args
+        .listen
+        .iter()
+        .map(|listen| listen.with_default_port(args.port))
+        .collect();
+    match (tls, args.tls_port) {
+        (false, _) => (listen_addrs, Vec::new()),
+        (true, None) => (Vec::new(), listen_addrs),
+        (true, Some(tls_port)) => {
+            let tls_addrs = args
+                .listen
+                .iter()
+                .map(|listen| listen.with_port(tls_port))
+                .collect();
+            (listen_addrs, tls_addrs)
+        }
+    }
+}
+
+/// The `/mcp` routes behind the optional rate-limit and bearer-auth layers and
+/// the metrics layer, plus the probe and metrics routes, which bypass them.
+fn build_router(state: AppState, args: &Args, readiness: Readiness) -> Router {
+    let metrics = state.metrics.clone();
+    // Build our application with routes for both /mcp and /mcp/
+    // This ensures compatibility with wrapper.py which adds trailing slashes
+    let mcp_routes = post(mcp_handler)
+        .get(sse_handler)
+        .delete(delete_session_handler);
+    let mut app = Router::new()
+        .route("/mcp", mcp_routes.clone())
+        .route("/mcp/", mcp_routes)
+        .with_state(state);
+
+    let limiter = RateLimiter::from_options(
+        args.rate_limit,
+        args.rate_limit_burst,
+        args.rate_limit_key,
+        args.max_concurrent_requests,
+        args.rate_limit_response,
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("[ERROR] Failed to configure rate limiting: {e}");
+        std::process::exit(1);
+    });
+    if let Some(limiter) = limiter {
+        app = app.layer(middleware::from_fn_with_state(
+            Arc::new(limiter),
+            ratelimit::limit,
+        ));
+    }
+
+    let auth = Auth::from_options(
+        args.auth_token.clone(),
+        args.jwt_secret.as_deref(),
+        args.jwt_public_key.as_deref(),
+        &args.jwt_audience,
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("[ERROR] Failed to configure authentication: {e}");
+        std::process::exit(1);
+    });
+    if let Some(auth) = auth {
+        app = app.layer(middleware::from_fn_with_state(
+            Arc::new(auth),
+            auth::require_bearer,
+        ));
+    }
+    // Outermost, so rejected credentials are counted and auth time is included.
+    app.layer(middleware::from_fn_with_state(
+        metrics.clone(),
+        metrics::track,
+    ))
+    .merge(health::router(readiness))
+    .merge(metrics::router(metrics))
+}
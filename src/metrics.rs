@@ -0,0 +1,209 @@
+use axum::{
+    extract::{Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use prometheus::{
+    core::Collector, exponential_buckets, HistogramOpts, HistogramVec, IntCounterVec, IntGauge,
+    Opts, Registry, TextEncoder,
+};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// JSON-RPC methods reported under their own label; anything else is counted
+/// as `other` so arbitrary client input cannot grow the label set.
+const KNOWN_METHODS: [&str; 6] = [
+    "initialize",
+    "ping",
+    "tools/list",
+    "tools/call",
+    "notifications/initialized",
+    "notifications/cancelled",
+];
+
+/// JSON-RPC error code of a transport-level error response, attached as a
+/// response extension so the HTTP middleware can count it.
+#[derive(Clone, Copy, Debug)]
+pub struct TransportErrorCode(pub i32);
+
+/// Server-side counters and latency histograms, exported at GET `/metrics`.
+pub struct Metrics {
+    registry: Registry,
+    requests: IntCounterVec,
+    tool_calls: IntCounterVec,
+    errors: IntCounterVec,
+    in_flight: IntGauge,
+    http_duration: HistogramVec,
+    message_duration: HistogramVec,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        // Handlers answer in microseconds, far below the default buckets:
+        // 50µs doubling up to ~1.6s.
+        let buckets = exponential_buckets(0.000_05, 2.0, 16).unwrap();
+        let metrics = Metrics {
+            registry: Registry::new(),
+            requests: IntCounterVec::new(
+                Opts::new(
+                    "mcp_requests_total",
+                    "JSON-RPC messages received, by method",
+                ),
+                &["method"],
+            )
+            .unwrap(),
+            tool_calls: IntCounterVec::new(
+                Opts::new("mcp_tool_calls_total", "tools/call requests, by tool"),
+                &["tool"],
+            )
+            .unwrap(),
+            errors: IntCounterVec::new(
+                Opts::new(
+                    "mcp_errors_total",
+                    "JSON-RPC error responses, by error code",
+                ),
+                &["code"],
+            )
+            .unwrap(),
+            in_flight: IntGauge::new(
+                "mcp_http_requests_in_flight",
+                "HTTP requests to /mcp currently being handled",
+            )
+            .unwrap(),
+            http_duration: HistogramVec::new(
+                HistogramOpts::new(
+                    "mcp_http_request_duration_seconds",
+                    "Time spent handling HTTP requests to /mcp, by status",
+                )
+                .buckets(buckets.clone()),
+                &["status"],
+            )
+            .unwrap(),
+            message_duration: HistogramVec::new(
+                HistogramOpts::new(
+                    "mcp_request_duration_seconds",
+                    "Time spent dispatching JSON-RPC messages, by method",
+                )
+                .buckets(buckets),
+                &["method"],
+            )
+            .unwrap(),
+        };
+        let collectors: [Box<dyn Collector>; 6] = [
+            Box::new(metrics.requests.clone()),
+            Box::new(metrics.tool_calls.clone()),
+            Box::new(metrics.errors.clone()),
+            Box::new(metrics.in_flight.clone()),
+            Box::new(metrics.http_duration.clone()),
+            Box::new(metrics.message_duration.clone()),
+        ];
+        for collector in collectors {
+            metrics.registry.register(collector).unwrap();
+        }
+        metrics
+    }
+}
+
+impl Metrics {
+    /// Label for a message's `method`: known methods as-is, `other` for the
+    /// rest and `invalid` when there is none.
+    pub fn method_label(method: Option<&str>) -> &'static str {
+        match method {
+            Some(method) => KNOWN_METHODS
+                .into_iter()
+                .find(|known| *known == method)
+                .unwrap_or("other"),
+            None => "invalid",
+        }
+    }
+
+    /// Records one dispatched JSON-RPC message. `tool` is set for calls to a
+    /// registered tool.
+    pub fn observe_message(
+        &self,
+        method: &'static str,
+        tool: Option<&'static str>,
+        response: Option<&Value>,
+        elapsed: Duration,
+    ) {
+        self.requests.with_label_values(&[method]).inc();
+        self.message_duration
+            .with_label_values(&[method])
+            .observe(elapsed.as_secs_f64());
+        if let Some(tool) = tool {
+            self.tool_calls.with_label_values(&[tool]).inc();
+        }
+        let code = response
+            .and_then(|response| response.pointer("/error/code"))
+            .and_then(Value::as_i64);
+        if let Some(code) = code {
+            self.observe_error(code);
+        }
+    }
+
+    /// Counts a JSON-RPC error response produced outside `observe_message`.
+    pub fn observe_error(&self, code: i64) {
+        self.errors.with_label_values(&[&code.to_string()]).inc();
+    }
+
+    fn render(&self) -> String {
+        TextEncoder::new()
+            .encode_to_string(&self.registry.gather())
+            .unwrap()
+    }
+}
+
+// --- HTTP ---
+
+/// GET `/metrics` in the Prometheus text format. Mounted outside the
+/// bearer-auth layer, like the health probes, so scrapers need no token.
+pub fn router(metrics: Arc<Metrics>) -> Router {
+    Router::new()
+        .route("/metrics", get(render))
+        .with_state(metrics)
+}
+
+async fn render(State(metrics): State<Arc<Metrics>>) -> Response {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics.render(),
+    )
+        .into_response()
+}
+
+/// Middleware around the `/mcp` routes: tracks in-flight requests, handler
+/// latency, and transport-level JSON-RPC errors.
+pub async fn track(State(metrics): State<Arc<Metrics>>, request: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let _in_flight = InFlight::new(&metrics.in_flight);
+    let response = next.run(request).await;
+
+    metrics
+        .http_duration
+        .with_label_values(&[response.status().as_str()])
+        .observe(started.elapsed().as_secs_f64());
+    if let Some(TransportErrorCode(code)) = response.extensions().get() {
+        metrics.errors.with_label_values(&[&code.to_string()]).inc();
+    }
+    response
+}
+
+// Decrements the gauge on drop, so requests abandoned by the client are released too.
+struct InFlight<'a>(&'a IntGauge);
+
+impl<'a> InFlight<'a> {
+    fn new(gauge: &'a IntGauge) -> Self {
+        gauge.inc();
+        InFlight(gauge)
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
@@ -0,0 +1,219 @@
+use axum_server::{Address, Server};
+use clap::ValueEnum;
+use socket2::{Domain, Socket, Type};
+use std::fs::{self, Permissions};
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::str::FromStr;
+
+/// Backlog passed to `listen(2)`; sized for benchmark bursts rather than the
+/// std default of 128.
+const LISTEN_BACKLOG: i32 = 1024;
+
+// --- Listen Addresses ---
+
+/// A `--listen` value: an IP address, optionally with its own port
+/// (`127.0.0.1`, `::1`, `[::1]`, `127.0.0.1:8080`, `[::1]:8080`).
+#[derive(Clone, Copy, Debug)]
+pub struct ListenAddr {
+    ip: IpAddr,
+    port: Option<u16>,
+}
+
+impl ListenAddr {
+    /// Resolves to a socket address, using `default_port` when none was given.
+    pub fn with_default_port(self, default_port: u16) -> SocketAddr {
+        SocketAddr::new(self.ip, self.port.unwrap_or(default_port))
+    }
+
+    /// Resolves to a socket address on `port`, ignoring any port given here.
+    pub fn with_port(self, port: u16) -> SocketAddr {
+        SocketAddr::new(self.ip, port)
+    }
+}
+
+impl FromStr for ListenAddr {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Ok(addr) = value.parse::<SocketAddr>() {
+            return Ok(ListenAddr {
+                ip: addr.ip(),
+                port: Some(addr.port()),
+            });
+        }
+        let ip = value.trim_start_matches('[').trim_end_matches(']');
+        ip.parse::<IpAddr>()
+            .map(|ip| ListenAddr { ip, port: None })
+            .map_err(|_| format!("'{value}' is not an IP address or IP:port"))
+    }
+}
+
+// --- HTTP Versions ---
+
+/// HTTP versions offered to clients (`--http`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum HttpMode {
+    /// HTTP/1.1 and HTTP/2: chosen by ALPN over TLS; cleartext connections
+    /// starting with the HTTP/2 preface are served as h2c (prior knowledge)
+    #[default]
+    Auto,
+    /// HTTP/1.1 only
+    Http1,
+    /// HTTP/2 only: ALPN `h2` over TLS, h2c with prior knowledge in cleartext
+    Http2,
+}
+
+impl HttpMode {
+    /// Protocols advertised in the TLS handshake, most preferred first.
+    pub fn alpn_protocols(self) -> Vec<Vec<u8>> {
+        match self {
+            HttpMode::Auto => vec![b"h2".to_vec(), b"http/1.1".to_vec()],
+            HttpMode::Http1 => vec![b"http/1.1".to_vec()],
+            HttpMode::Http2 => vec![b"h2".to_vec()],
+        }
+    }
+
+    /// Restricts the connection handling of `server` to this mode.
+    pub fn configure<A: Address, Acc>(self, server: Server<A, Acc>) -> Server<A, Acc> {
+        match self {
+            HttpMode::Auto => server,
+            HttpMode::Http1 => server.http1_only(),
+            HttpMode::Http2 => server.http2_only(),
+        }
+    }
+}
+
+// --- Binding ---
+
+/// Binds a non-blocking TCP listener. IPv6 sockets are made v6-only so that
+/// `0.0.0.0` and `::` can be listened on side by side with the same port.
+pub fn bind_tcp(addr: SocketAddr) -> io::Result<std::net::TcpListener> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, None)?;
+    if addr.is_ipv6() {
+        socket.set_only_v6(true)?;
+    }
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(LISTEN_BACKLOG)?;
+    Ok(socket.into())
+}
+
+/// Binds a non-blocking Unix domain socket listener at `path`.
+///
+/// A socket file left behind by a previous run is removed, but only when
+/// nothing accepts connections on it; a live server or a non-socket file at
+/// `path` is an error. `mode`, if given, is applied as the socket file is created.
+pub fn bind_unix(path: &Path, mode: Option<u32>) -> io::Result<UnixListener> {
+    if let Ok(metadata) = fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "path exists and is not a socket",
+            ));
+        }
+        if UnixStream::connect(path).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                "another server is listening on this socket",
+            ));
+        }
+        fs::remove_file(path)?;
+    }
+
+    let listener = match mode {
+        // Create the socket with at most `mode`'s permission bits so it is
+        // never reachable by other users before `set_permissions` runs.
+        Some(mode) => {
+            // SAFETY: umask only swaps the process file-creation mask, and the
+            // previous mask is restored right after the bind.
+            let previous = unsafe { libc::umask((!mode & 0o777) as libc::mode_t) };
+            let bound = UnixListener::bind(path);
+            unsafe { libc::umask(previous) };
+            bound?
+        }
+        None => UnixListener::bind(path)?,
+    };
+    listener.set_nonblocking(true)?;
+    if let Some(mode) = mode {
+        fs::set_permissions(path, Permissions::from_mode(mode))?;
+    }
+    Ok(listener)
+}
+
+/// Parses a `--unix-socket-mode` value such as `660` or `0o660`.
+pub fn parse_mode(value: &str) -> Result<u32, String> {
+    let digits = value.trim_start_matches("0o");
+    u32::from_str_radix(digits, 8)
+        .ok()
+        .filter(|mode| *mode <= 0o7777)
+        .ok_or_else(|| format!("'{value}' is not an octal permission mode"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(value: &str) -> SocketAddr {
+        value.parse::<ListenAddr>().unwrap().with_default_port(3000)
+    }
+
+    #[test]
+    fn listen_addr_without_port_uses_the_default() {
+        assert_eq!(resolve("127.0.0.1"), "127.0.0.1:3000".parse().unwrap());
+        assert_eq!(resolve("0.0.0.0"), "0.0.0.0:3000".parse().unwrap());
+        assert_eq!(resolve("::1"), "[::1]:3000".parse().unwrap());
+        assert_eq!(resolve("[::1]"), "[::1]:3000".parse().unwrap());
+    }
+
+    #[test]
+    fn listen_addr_with_port_keeps_it() {
+        assert_eq!(resolve("127.0.0.1:8080"), "127.0.0.1:8080".parse().unwrap());
+        assert_eq!(resolve("[::]:8080"), "[::]:8080".parse().unwrap());
+    }
+
+    #[test]
+    fn with_port_overrides_the_given_port() {
+        let listen: ListenAddr = "127.0.0.1:8080".parse().unwrap();
+        assert_eq!(listen.with_port(3443), "127.0.0.1:3443".parse().unwrap());
+    }
+
+    #[test]
+    fn listen_addr_rejects_host_names_and_junk() {
+        for value in ["localhost", "localhost:3000", "1.2.3", "127.0.0.1:port", ""] {
+            assert_eq!(
+                value.parse::<ListenAddr>().unwrap_err(),
+                format!("'{value}' is not an IP address or IP:port")
+            );
+        }
+    }
+
+    #[test]
+    fn parse_mode_reads_octal() {
+        assert_eq!(parse_mode("660"), Ok(0o660));
+        assert_eq!(parse_mode("0o600"), Ok(0o600));
+        assert_eq!(parse_mode("0755"), Ok(0o755));
+        assert_eq!(parse_mode("7777"), Ok(0o7777));
+    }
+
+    #[test]
+    fn parse_mode_rejects_non_octal_and_out_of_range() {
+        for value in ["", "8", "rw-r--r--", "17777", "0x1ff"] {
+            assert!(parse_mode(value).is_err(), "{value} should be rejected");
+        }
+    }
+
+    #[test]
+    fn bind_unix_applies_the_mode() {
+        let path = std::env::temp_dir().join(format!("mcp-net-test-{}.sock", std::process::id()));
+        let listener = bind_unix(&path, Some(0o600)).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        drop(listener);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(mode & 0o7777, 0o600);
+    }
+}
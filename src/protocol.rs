@@ -0,0 +1,59 @@
+/// MCP protocol revisions this server can speak, oldest first so that `Ord`
+/// follows the revision dates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProtocolVersion {
+    V2024_11_05,
+    V2025_03_26,
+    V2025_06_18,
+    V2025_11_25,
+}
+
+impl ProtocolVersion {
+    pub const SUPPORTED: [ProtocolVersion; 4] = [
+        ProtocolVersion::V2024_11_05,
+        ProtocolVersion::V2025_03_26,
+        ProtocolVersion::V2025_06_18,
+        ProtocolVersion::V2025_11_25,
+    ];
+
+    pub const LATEST: ProtocolVersion = ProtocolVersion::V2025_11_25;
+
+    /// Assumed when nothing was negotiated: the Streamable HTTP transport
+    /// specifies 2025-03-26 for requests without an `MCP-Protocol-Version` header.
+    pub const FALLBACK: ProtocolVersion = ProtocolVersion::V2025_03_26;
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProtocolVersion::V2024_11_05 => "2024-11-05",
+            ProtocolVersion::V2025_03_26 => "2025-03-26",
+            ProtocolVersion::V2025_06_18 => "2025-06-18",
+            ProtocolVersion::V2025_11_25 => "2025-11-25",
+        }
+    }
+
+    pub fn parse(version: &str) -> Option<ProtocolVersion> {
+        Self::SUPPORTED
+            .into_iter()
+            .find(|supported| supported.as_str() == version)
+    }
+
+    /// The client's requested revision if we support it, otherwise our latest.
+    pub fn negotiate(requested: &str) -> ProtocolVersion {
+        Self::parse(requested).unwrap_or(Self::LATEST)
+    }
+
+    /// Tool `annotations` were added in 2025-03-26.
+    pub fn supports_tool_annotations(self) -> bool {
+        self >= ProtocolVersion::V2025_03_26
+    }
+
+    /// JSON-RPC batches were removed in 2025-06-18.
+    pub fn supports_batching(self) -> bool {
+        self < ProtocolVersion::V2025_06_18
+    }
+
+    /// `outputSchema` and `structuredContent` were added in 2025-06-18.
+    pub fn supports_structured_output(self) -> bool {
+        self >= ProtocolVersion::V2025_06_18
+    }
+}
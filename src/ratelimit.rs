@@ -0,0 +1,270 @@
+use crate::auth::BearerSubject;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use clap::ValueEnum;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+/// Number of tracked clients above which refilled buckets are dropped before
+/// a new one is added.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// What the token buckets are keyed by (`--rate-limit-key`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum RateLimitKey {
+    /// Bearer token subject (JWT `sub`, or the static token); client IP without one
+    Subject,
+    /// Client IP address
+    #[default]
+    Ip,
+    /// Mcp-Session-Id header; client IP without one
+    Session,
+}
+
+/// How rejected requests are answered (`--rate-limit-response`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum RateLimitResponse {
+    /// HTTP 429 with a Retry-After header
+    #[default]
+    Status,
+    /// HTTP 200 carrying a JSON-RPC error (Retry-After is still set)
+    JsonRpc,
+}
+
+// --- Configuration ---
+
+/// Load shedding for the MCP routes: a token bucket per client and a global
+/// cap on requests being handled at once. Both are optional.
+pub struct RateLimiter {
+    buckets: Option<Buckets>,
+    concurrency: Option<Semaphore>,
+    key: RateLimitKey,
+    response: RateLimitResponse,
+}
+
+impl RateLimiter {
+    /// Builds the limiter from the CLI options. `rate` is in requests per
+    /// second and `burst` defaults to one second's worth. Returns `Ok(None)`
+    /// when neither limit is configured.
+    pub fn from_options(
+        rate: Option<f64>,
+        burst: Option<u32>,
+        key: RateLimitKey,
+        max_concurrent: Option<usize>,
+        response: RateLimitResponse,
+    ) -> anyhow::Result<Option<Self>> {
+        let buckets = match rate {
+            Some(rate) if !(rate.is_finite() && rate > 0.0) => {
+                anyhow::bail!("--rate-limit must be a positive number, got {rate}")
+            }
+            Some(rate) => Some(Buckets {
+                rate,
+                burst: burst.map_or(rate.ceil(), f64::from).max(1.0),
+                clients: Mutex::default(),
+            }),
+            None => None,
+        };
+        let concurrency = match max_concurrent {
+            Some(0) => anyhow::bail!("--max-concurrent-requests must be at least 1"),
+            max => max.map(Semaphore::new),
+        };
+
+        if buckets.is_none() && concurrency.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(RateLimiter {
+            buckets,
+            concurrency,
+            key,
+            response,
+        }))
+    }
+
+    fn client_key(&self, request: &Request) -> String {
+        let key = match self.key {
+            RateLimitKey::Subject => request
+                .extensions()
+                .get::<BearerSubject>()
+                .map(|subject| format!("sub:{}", subject.0)),
+            RateLimitKey::Session => request
+                .headers()
+                .get(crate::MCP_SESSION_ID)
+                .and_then(|value| value.to_str().ok())
+                .map(|id| format!("session:{id}")),
+            RateLimitKey::Ip => None,
+        };
+        // Unix socket peers have no address and share one bucket.
+        key.unwrap_or_else(|| match request.extensions().get() {
+            Some(ConnectInfo(addr)) => SocketAddr::ip(addr).to_canonical().to_string(),
+            None => "unix".to_string(),
+        })
+    }
+
+    fn reject(&self, reason: &str, retry_after: Duration) -> Response {
+        // Whole seconds, rounded up.
+        let seconds = (retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0)).max(1);
+        let mut response = crate::create_transport_error(
+            StatusCode::TOO_MANY_REQUESTS,
+            -32000,
+            &format!("Too Many Requests: {reason}, retry after {seconds}s"),
+        );
+        if self.response == RateLimitResponse::JsonRpc {
+            *response.status_mut() = StatusCode::OK;
+        }
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+        response
+    }
+}
+
+struct Buckets {
+    rate: f64,
+    burst: f64,
+    clients: Mutex<HashMap<String, Bucket>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Buckets {
+    /// Takes a token from `key`'s bucket at time `now`, or returns how long
+    /// until one is available.
+    fn take(&self, key: String, now: Instant) -> Result<(), Duration> {
+        let refilled = |bucket: &Bucket| {
+            let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+            (bucket.tokens + elapsed * self.rate).min(self.burst)
+        };
+
+        let mut clients = self.clients.lock().unwrap();
+        if clients.len() >= PRUNE_THRESHOLD && !clients.contains_key(&key) {
+            // A full bucket behaves exactly like a new one, so nothing is lost.
+            clients.retain(|_, bucket| refilled(bucket) < self.burst);
+        }
+        let bucket = clients.entry(key).or_insert(Bucket {
+            tokens: self.burst,
+            updated: now,
+        });
+        bucket.tokens = refilled(bucket);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate))
+        }
+    }
+}
+
+// --- Middleware ---
+
+/// Sheds requests over the concurrency cap or their client's rate limit,
+/// answering with 429 (or a JSON-RPC error) and `Retry-After`. Sits inside the
+/// bearer-auth layer so the token subject is known.
+pub async fn limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let _permit = match &limiter.concurrency {
+        Some(semaphore) => match semaphore.try_acquire() {
+            Ok(permit) => Some(permit),
+            Err(_) => {
+                return limiter.reject("too many concurrent requests", Duration::from_secs(1))
+            }
+        },
+        None => None,
+    };
+    if let Some(buckets) = &limiter.buckets {
+        if let Err(wait) = buckets.take(limiter.client_key(&request), Instant::now()) {
+            return limiter.reject("rate limit exceeded", wait);
+        }
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buckets(rate: f64, burst: f64) -> Buckets {
+        Buckets {
+            rate,
+            burst,
+            clients: Mutex::default(),
+        }
+    }
+
+    #[test]
+    fn burst_is_allowed_then_rejected() {
+        let buckets = buckets(2.0, 3.0);
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert!(buckets.take("a".to_string(), now).is_ok());
+        }
+        let wait = buckets.take("a".to_string(), now).unwrap_err();
+        assert_eq!(wait, Duration::from_millis(500));
+    }
+
+    #[test]
+    fn clients_have_separate_buckets() {
+        let buckets = buckets(1.0, 1.0);
+        let now = Instant::now();
+        assert!(buckets.take("a".to_string(), now).is_ok());
+        assert!(buckets.take("a".to_string(), now).is_err());
+        assert!(buckets.take("b".to_string(), now).is_ok());
+    }
+
+    #[test]
+    fn tokens_refill_at_the_rate() {
+        let buckets = buckets(4.0, 1.0);
+        let start = Instant::now();
+        assert!(buckets.take("a".to_string(), start).is_ok());
+        let wait = buckets
+            .take("a".to_string(), start + Duration::from_millis(125))
+            .unwrap_err();
+        assert_eq!(wait, Duration::from_millis(125));
+        assert!(buckets
+            .take("a".to_string(), start + Duration::from_millis(250))
+            .is_ok());
+    }
+
+    #[test]
+    fn refill_is_capped_at_the_burst() {
+        let buckets = buckets(10.0, 2.0);
+        let start = Instant::now();
+        assert!(buckets.take("a".to_string(), start).is_ok());
+        let later = start + Duration::from_mins(1);
+        assert!(buckets.take("a".to_string(), later).is_ok());
+        assert!(buckets.take("a".to_string(), later).is_ok());
+        assert!(buckets.take("a".to_string(), later).is_err());
+    }
+
+    #[test]
+    fn full_buckets_are_pruned_past_the_threshold() {
+        let buckets = buckets(1.0, 2.0);
+        let start = Instant::now();
+        for client in 0..PRUNE_THRESHOLD {
+            assert!(buckets.take(client.to_string(), start).is_ok());
+        }
+        // Client 0 drains its bucket again just before the sweep, so only it
+        // is still below the burst and kept.
+        let later = start + Duration::from_secs(5);
+        assert!(buckets.take("0".to_string(), later).is_ok());
+        assert!(buckets.take("0".to_string(), later).is_ok());
+        assert!(buckets.take("new".to_string(), later).is_ok());
+
+        let clients = buckets.clients.lock().unwrap();
+        assert_eq!(clients.len(), 2);
+        assert!(clients.contains_key("0") && clients.contains_key("new"));
+    }
+}
@@ -0,0 +1,220 @@
+use crate::protocol::ProtocolVersion;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Capacity of the per-session channel feeding GET `/mcp` SSE streams.
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+
+/// Longest pause between sweeps for idle sessions.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+// --- Session State ---
+
+/// State kept for one MCP session: from `initialize` until DELETE or idle
+/// expiry over HTTP, or for the lifetime of the process over stdio.
+#[derive(Debug)]
+pub struct Session {
+    /// Server-initiated messages; every open GET `/mcp` stream subscribes here.
+    /// Nothing sends on it yet, as no tool produces notifications, so the
+    /// streams only carry keep-alives. Dropping the session drops the sender,
+    /// which ends those streams.
+    events: broadcast::Sender<Value>,
+    /// Revision agreed on in `initialize`.
+    protocol_version: OnceLock<ProtocolVersion>,
+    /// Set once the client has sent `notifications/initialized`.
+    initialized: AtomicBool,
+    /// Requests currently being handled, keyed by the JSON text of their id.
+    in_flight: Mutex<HashMap<String, InFlight>>,
+    /// Subject of the mutual-TLS client certificate that opened the session;
+    /// later requests must present the same one.
+    client: Option<String>,
+    /// When the session was last looked up by a request.
+    last_seen: Mutex<Instant>,
+}
+
+/// In-flight requests sharing one id.
+#[derive(Debug, Default)]
+struct InFlight {
+    /// Ids should be unique within a session, but concurrent duplicates are
+    /// counted so one finishing does not forget the others.
+    running: usize,
+    /// A `notifications/cancelled` arrived for the id.
+    cancelled: bool,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Session {
+            events,
+            protocol_version: OnceLock::new(),
+            initialized: AtomicBool::new(false),
+            in_flight: Mutex::new(HashMap::new()),
+            client: None,
+            last_seen: Mutex::new(Instant::now()),
+        }
+    }
+}
+
+impl Session {
+    pub fn for_client(client: Option<String>) -> Self {
+        Session {
+            client,
+            ..Session::default()
+        }
+    }
+
+    pub fn client(&self) -> Option<&str> {
+        self.client.as_deref()
+    }
+
+    fn touch(&self, now: Instant) {
+        *self.last_seen.lock().unwrap() = now;
+    }
+
+    /// Only requests count as activity: a GET `/mcp` stream carries no traffic
+    /// yet, so holding one open does not keep a session from expiring.
+    fn is_idle(&self, now: Instant, timeout: Duration) -> bool {
+        now.duration_since(*self.last_seen.lock().unwrap()) >= timeout
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Value> {
+        self.events.subscribe()
+    }
+
+    /// Records the negotiated revision. Only the first `initialize` of a
+    /// session counts; repeats are ignored.
+    pub fn set_protocol_version(&self, version: ProtocolVersion) {
+        let _ = self.protocol_version.set(version);
+    }
+
+    pub fn protocol_version(&self) -> Option<ProtocolVersion> {
+        self.protocol_version.get().copied()
+    }
+
+    pub fn mark_initialized(&self) {
+        self.initialized.store(true, Ordering::Release);
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.initialized.load(Ordering::Acquire)
+    }
+
+    /// Registers a request as in flight and returns the key to pass to
+    /// [`Session::finish_request`].
+    pub fn begin_request(&self, id: &Value) -> String {
+        let key = id.to_string();
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if let Some(entry) = in_flight.get_mut(&key) {
+            entry.running += 1;
+        } else {
+            let entry = InFlight {
+                running: 1,
+                cancelled: false,
+            };
+            in_flight.insert(key.clone(), entry);
+        }
+        key
+    }
+
+    /// Flags an in-flight request as cancelled. Ids that already completed or
+    /// were never seen are ignored, as the cancellation spec allows.
+    pub fn cancel_request(&self, id: &Value) {
+        if let Some(entry) = self.in_flight.lock().unwrap().get_mut(&id.to_string()) {
+            entry.cancelled = true;
+        }
+    }
+
+    /// Marks a request as finished, returning `true` if it was cancelled
+    /// while running.
+    pub fn finish_request(&self, key: &str) -> bool {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        let Some(entry) = in_flight.get_mut(key) else {
+            return false;
+        };
+        let cancelled = entry.cancelled;
+        entry.running -= 1;
+        if entry.running == 0 {
+            in_flight.remove(key);
+        }
+        cancelled
+    }
+}
+
+// --- Session Store ---
+
+/// Sessions keyed by the `Mcp-Session-Id` value handed out on `initialize`.
+/// Clients that never send DELETE are bounded by `max_sessions` and by idle
+/// expiry (see [`SessionStore::expire_idle`]).
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    sessions: Arc<RwLock<HashMap<String, Arc<Session>>>>,
+    max_sessions: usize,
+    idle_timeout: Duration,
+}
+
+impl SessionStore {
+    pub fn new(max_sessions: usize, idle_timeout: Duration) -> Self {
+        SessionStore {
+            sessions: Arc::default(),
+            max_sessions,
+            idle_timeout,
+        }
+    }
+
+    /// Registers a session after a successful `initialize` and returns its id,
+    /// or `None` when `max_sessions` are already open.
+    pub fn insert(&self, session: Arc<Session>) -> Option<String> {
+        let mut sessions = self.sessions.write().unwrap();
+        if sessions.len() >= self.max_sessions {
+            return None;
+        }
+        let id = Uuid::new_v4().to_string();
+        sessions.insert(id.clone(), session);
+        Some(id)
+    }
+
+    /// Looks up a session and marks it as active.
+    pub fn get(&self, id: &str) -> Option<Arc<Session>> {
+        let session = self.sessions.read().unwrap().get(id).cloned()?;
+        session.touch(Instant::now());
+        Some(session)
+    }
+
+    /// Periodically ends sessions that have not been used for `idle_timeout`,
+    /// closing any SSE streams they still have open. Runs for the lifetime of
+    /// the server.
+    pub async fn expire_idle(self) {
+        let period = self
+            .idle_timeout
+            .min(SWEEP_INTERVAL)
+            .max(Duration::from_secs(1));
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            let now = Instant::now();
+            let mut sessions = self.sessions.write().unwrap();
+            let before = sessions.len();
+            sessions.retain(|_, session| !session.is_idle(now, self.idle_timeout));
+            let expired = before - sessions.len();
+            if expired > 0 {
+                tracing::info!(expired, open = sessions.len(), "expired idle sessions");
+            }
+        }
+    }
+
+    /// Ends every session, closing their SSE streams.
+    pub fn clear(&self) {
+        self.sessions.write().unwrap().clear();
+    }
+
+    /// Ends a session. Returns `false` if the id was unknown.
+    pub fn remove(&self, id: &str) -> bool {
+        self.sessions.write().unwrap().remove(id).is_some()
+    }
+}
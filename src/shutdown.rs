@@ -0,0 +1,72 @@
+use crate::health::Readiness;
+use crate::session::SessionStore;
+use axum_server::{Address, Handle};
+use std::io;
+use std::time::{Duration, Instant};
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::task::JoinSet;
+
+/// Drives `servers` until one fails or the process receives SIGINT/SIGTERM.
+///
+/// The server is reported ready for the duration. On a signal it turns
+/// unready, every listener stops accepting, all sessions are ended (closing
+/// their SSE streams, which would otherwise hold connections open), and
+/// connections get up to `timeout` to finish their in-flight requests before
+/// being closed. A one-line summary is printed once everything has stopped.
+pub async fn run_until_signal<A: Address>(
+    mut servers: JoinSet<io::Result<()>>,
+    handle: Handle<A>,
+    sessions: SessionStore,
+    readiness: Readiness,
+    timeout: Duration,
+) {
+    readiness.set(true);
+    let signal = wait_for_signal();
+    tokio::pin!(signal);
+    let mut draining = None;
+
+    loop {
+        tokio::select! {
+            result = servers.join_next() => {
+                let Some(result) = result else { break };
+                if let Err(e) = result.unwrap() {
+                    tracing::error!(error = %e, "server failed");
+                    std::process::exit(1);
+                }
+            }
+            name = &mut signal, if draining.is_none() => {
+                let open = handle.connection_count();
+                println!(
+                    "Received {name}, draining {open} open connection(s) (timeout {}s)",
+                    timeout.as_secs()
+                );
+                readiness.set(false);
+                sessions.clear();
+                handle.graceful_shutdown(Some(timeout));
+                draining = Some((Instant::now(), open));
+            }
+        }
+    }
+
+    if let Some((started, open)) = draining {
+        let elapsed = started.elapsed();
+        let outcome = if elapsed >= timeout {
+            "drain timeout reached, remaining connections were closed"
+        } else {
+            "all connections drained"
+        };
+        println!(
+            "Shutdown complete in {:.3}s: {open} connection(s) open at signal, {outcome}",
+            elapsed.as_secs_f64()
+        );
+    }
+}
+
+async fn wait_for_signal() -> &'static str {
+    let mut interrupt = signal(SignalKind::interrupt()).expect("failed to install SIGINT handler");
+    let mut terminate = signal(SignalKind::terminate()).expect("failed to install SIGTERM handler");
+    tokio::select! {
+        _ = interrupt.recv() => "SIGINT",
+        _ = terminate.recv() => "SIGTERM",
+    }
+}
@@ -0,0 +1,78 @@
+use axum::http::{HeaderMap, HeaderName};
+use opentelemetry::propagation::{Extractor, TextMapPropagator};
+use opentelemetry::trace::TraceContextExt;
+use opentelemetry::Context;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use serde_json::{Map, Value};
+
+// --- Export ---
+
+/// Batches spans to an OTLP/HTTP collector at `endpoint`, the full traces URL
+/// (e.g. `http://localhost:4318/v1/traces`).
+pub fn tracer_provider(endpoint: &str) -> anyhow::Result<SdkTracerProvider> {
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()?;
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(env!("CARGO_PKG_NAME"))
+                .build(),
+        )
+        .build())
+}
+
+// --- Propagation ---
+
+/// Remote parent from the W3C `traceparent`/`tracestate` HTTP headers.
+pub fn from_headers(headers: &HeaderMap) -> Option<Context> {
+    headers
+        .contains_key("traceparent")
+        .then(|| extract(&HeaderExtractor(headers)))
+        .flatten()
+}
+
+/// Remote parent from the `traceparent`/`tracestate` keys of a request's
+/// `params._meta`, which lets clients propagate context per message, e.g.
+/// across a stdio wrapper that has no HTTP headers to carry it.
+pub fn from_meta(meta: &Value) -> Option<Context> {
+    let meta = meta.as_object()?;
+    meta.contains_key("traceparent")
+        .then(|| extract(&MetaExtractor(meta)))
+        .flatten()
+}
+
+// Malformed values extract to an invalid span context, treated as no parent.
+fn extract(carrier: &dyn Extractor) -> Option<Context> {
+    let context = TraceContextPropagator::new().extract(carrier);
+    context.span().span_context().is_valid().then_some(context)
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(HeaderName::as_str).collect()
+    }
+}
+
+struct MetaExtractor<'a>(&'a Map<String, Value>);
+
+impl Extractor for MetaExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(Value::as_str)
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(String::as_str).collect()
+    }
+}
@@ -0,0 +1,173 @@
+use crate::net::HttpMode;
+use axum::{middleware::AddExtension, Extension};
+use axum_server::accept::Accept;
+use axum_server::tls_rustls::{RustlsAcceptor, RustlsConfig};
+use std::fs::File;
+use std::future::Future;
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::signal::unix::{signal, SignalKind};
+use tokio_rustls::rustls::pki_types::CertificateDer;
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{RootCertStore, ServerConfig};
+use tokio_rustls::server::TlsStream;
+use tower_layer::Layer;
+
+/// How often the certificate files are checked for changes.
+const RELOAD_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// --- Configuration ---
+
+/// Certificate material for the HTTPS listeners.
+#[derive(Clone, Debug)]
+pub struct TlsFiles {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+    /// CA bundle that client certificates must chain to; enables mutual TLS.
+    pub client_ca: Option<PathBuf>,
+    /// Decides the protocols advertised over ALPN.
+    pub http_mode: HttpMode,
+}
+
+impl TlsFiles {
+    /// Reads the PEM files into a rustls server config.
+    pub fn load(&self) -> anyhow::Result<ServerConfig> {
+        let certs = read_certs(&self.cert)?;
+        let key = rustls_pemfile::private_key(&mut BufReader::new(File::open(&self.key)?))?
+            .ok_or_else(|| anyhow::anyhow!("no private key found in {}", self.key.display()))?;
+
+        let builder = ServerConfig::builder();
+        let builder = match &self.client_ca {
+            Some(path) => {
+                let mut roots = RootCertStore::empty();
+                for cert in read_certs(path)? {
+                    roots.add(cert)?;
+                }
+                builder.with_client_cert_verifier(
+                    WebPkiClientVerifier::builder(Arc::new(roots)).build()?,
+                )
+            }
+            None => builder.with_no_client_auth(),
+        };
+        let mut config = builder.with_single_cert(certs, key)?;
+        config.alpn_protocols = self.http_mode.alpn_protocols();
+        Ok(config)
+    }
+
+    pub fn requires_client_cert(&self) -> bool {
+        self.client_ca.is_some()
+    }
+
+    // Modification times are compared rather than watched with inotify so that
+    // atomic renames and symlink swaps (as done by cert-manager) are picked up too.
+    fn modified(&self) -> Vec<Option<SystemTime>> {
+        [Some(&self.cert), Some(&self.key), self.client_ca.as_ref()]
+            .into_iter()
+            .flatten()
+            .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+            .collect()
+    }
+}
+
+// --- Hot Reload ---
+
+/// Reloads `config` whenever the certificate files change or the process
+/// receives SIGHUP. Existing connections keep the config they were accepted
+/// with; a file set that fails to load is reported and the old config stays active.
+pub async fn reload_on_change(files: TlsFiles, config: RustlsConfig) {
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => Some(hangup),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to install SIGHUP handler");
+            None
+        }
+    };
+    let mut poll = tokio::time::interval(RELOAD_POLL_INTERVAL);
+    let mut last_modified = files.modified();
+
+    loop {
+        tokio::select! {
+            _ = poll.tick() => {
+                let modified = files.modified();
+                if modified == last_modified {
+                    continue;
+                }
+                last_modified = modified;
+            }
+            Some(()) = async { hangup.as_mut()?.recv().await } => {
+                last_modified = files.modified();
+            }
+        }
+        match files.load() {
+            Ok(server_config) => {
+                config.reload_from_config(Arc::new(server_config));
+                tracing::info!(cert = %files.cert.display(), "reloaded TLS certificate");
+            }
+            Err(e) => tracing::error!(error = %e, "failed to reload TLS certificate/key"),
+        }
+    }
+}
+
+fn read_certs(path: &Path) -> anyhow::Result<Vec<CertificateDer<'static>>> {
+    let certs = rustls_pemfile::certs(&mut BufReader::new(File::open(path)?))
+        .collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        anyhow::bail!("no certificates found in {}", path.display());
+    }
+    Ok(certs)
+}
+
+// --- Client Certificates ---
+
+/// Subject DN of the verified client certificate (e.g. `CN=gateway, O=Example`),
+/// attached as a request extension on mutual-TLS connections.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClientCertSubject(pub String);
+
+/// Performs the TLS handshake, then tags every request on the connection with
+/// the client certificate's subject.
+#[derive(Clone, Debug)]
+pub struct ClientCertAcceptor {
+    inner: RustlsAcceptor,
+}
+
+impl ClientCertAcceptor {
+    pub fn new(config: RustlsConfig) -> Self {
+        ClientCertAcceptor {
+            inner: RustlsAcceptor::new(config),
+        }
+    }
+}
+
+impl<I, S> Accept<I, S> for ClientCertAcceptor
+where
+    I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    S: Send + 'static,
+{
+    type Stream = TlsStream<I>;
+    type Service = AddExtension<S, ClientCertSubject>;
+    type Future = Pin<Box<dyn Future<Output = io::Result<(Self::Stream, Self::Service)>> + Send>>;
+
+    fn accept(&self, stream: I, service: S) -> Self::Future {
+        let handshake = self.inner.accept(stream, service);
+        Box::pin(async move {
+            let (stream, service) = handshake.await?;
+            // The verifier rejects handshakes without a certificate, so one is present.
+            let subject = stream
+                .get_ref()
+                .1
+                .peer_certificates()
+                .and_then(<[_]>::first)
+                .and_then(|cert| x509_parser::parse_x509_certificate(cert).ok())
+                .map(|(_, cert)| cert.subject().to_string())
+                .ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidData, "unreadable client certificate")
+                })?;
+            Ok((stream, Extension(ClientCertSubject(subject)).layer(service)))
+        })
+    }
+}
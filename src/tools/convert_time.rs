@@ -0,0 +1,162 @@
+use super::{call_result, parse_timezone, Tool, ToolError};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDateTime, NaiveTime, Offset, SecondsFormat, TimeZone, Utc};
+use chrono_tz::{OffsetComponents, Tz};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// `convert_time`: converts a wall-clock time from one IANA timezone to another.
+/// Arguments and output mirror the reference Python MCP time server.
+pub struct ConvertTime;
+
+#[derive(Deserialize, Debug)]
+struct ToolArguments {
+    source_timezone: String,
+    time: String,
+    target_timezone: String,
+}
+
+#[async_trait]
+impl Tool for ConvertTime {
+    fn name(&self) -> &'static str {
+        "convert_time"
+    }
+
+    fn description(&self) -> &'static str {
+        "Convert time between timezones"
+    }
+
+    fn input_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "source_timezone": {
+                    "type": "string",
+                    "description": "Source IANA timezone name (e.g., 'America/New_York', 'Europe/London')"
+                },
+                "time": {
+                    "type": "string",
+                    "description": "Time to convert in 24-hour format (HH:MM) for today in the source timezone, or a local date and time (YYYY-MM-DDTHH:MM[:SS])"
+                },
+                "target_timezone": {
+                    "type": "string",
+                    "description": "Target IANA timezone name (e.g., 'Asia/Tokyo', 'America/San_Francisco')"
+                }
+            },
+            "required": ["source_timezone", "time", "target_timezone"]
+        })
+    }
+
+    fn annotations(&self) -> Value {
+        serde_json::json!({
+            "title": "Convert Time",
+            "readOnlyHint": true,
+            "destructiveHint": false,
+            "idempotentHint": true,
+            "openWorldHint": false
+        })
+    }
+
+    async fn call(&self, arguments: Value) -> Result<Value, ToolError> {
+        let args: ToolArguments = serde_json::from_value(arguments).map_err(|e| {
+            ToolError::InvalidParams(format!("Invalid params for convert_time: {e}"))
+        })?;
+        let source_tz = resolve_timezone(&args.source_timezone)?;
+        let target_tz = resolve_timezone(&args.target_timezone)?;
+
+        let local = parse_local_time(&args.time, source_tz)?;
+        let source_time = source_tz
+            .from_local_datetime(&local)
+            // Ambiguous times (DST fall-back) resolve to the earlier instant.
+            .earliest()
+            .ok_or_else(|| {
+                ToolError::InvalidParams(format!(
+                    "Invalid params: {} does not exist in {source_tz} (skipped by a DST transition)",
+                    args.time
+                ))
+            })?;
+        let target_time = source_time.with_timezone(&target_tz);
+
+        let offset_seconds = target_time.offset().fix().local_minus_utc()
+            - source_time.offset().fix().local_minus_utc();
+        let result = serde_json::json!({
+            "source": describe(&source_time),
+            "target": describe(&target_time),
+            "time_difference": format_hours(offset_seconds)
+        });
+
+        Ok(call_result(vec![serde_json::json!({
+            "type": "text",
+            "text": serde_json::to_string_pretty(&result).unwrap()
+        })]))
+    }
+}
+
+fn resolve_timezone(name: &str) -> Result<Tz, ToolError> {
+    if name.is_empty() {
+        return Err(ToolError::InvalidParams(
+            "Invalid params: timezone must not be empty".to_string(),
+        ));
+    }
+    parse_timezone(name).ok_or_else(|| {
+        ToolError::InvalidParams(format!("Invalid params: unknown timezone '{name}'"))
+    })
+}
+
+// "HH:MM" means that time today in the source zone; otherwise a full local date-time.
+fn parse_local_time(time: &str, source_tz: Tz) -> Result<NaiveDateTime, ToolError> {
+    if let Ok(clock) = NaiveTime::parse_from_str(time, "%H:%M") {
+        return Ok(Utc::now()
+            .with_timezone(&source_tz)
+            .date_naive()
+            .and_time(clock));
+    }
+    ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(time, format).ok())
+        .ok_or_else(|| {
+            ToolError::InvalidParams(
+                "Invalid time format. Expected HH:MM [24-hour format] or YYYY-MM-DDTHH:MM[:SS]"
+                    .to_string(),
+            )
+        })
+}
+
+fn describe(time: &DateTime<Tz>) -> Value {
+    serde_json::json!({
+        "timezone": time.timezone().name(),
+        "datetime": time.to_rfc3339_opts(SecondsFormat::Secs, false),
+        "is_dst": !time.offset().dst_offset().is_zero()
+    })
+}
+
+// Matches the reference server: "+13.0h" for whole hours, "+5.75h" otherwise.
+fn format_hours(offset_seconds: i32) -> String {
+    let hours = f64::from(offset_seconds) / 3600.0;
+    if offset_seconds % 3600 == 0 {
+        format!("{hours:+.1}h")
+    } else {
+        let hours = format!("{hours:+.2}");
+        format!("{}h", hours.trim_end_matches('0').trim_end_matches('.'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whole_hours_keep_one_decimal() {
+        assert_eq!(format_hours(13 * 3600), "+13.0h");
+        assert_eq!(format_hours(-5 * 3600), "-5.0h");
+        assert_eq!(format_hours(0), "+0.0h");
+    }
+
+    #[test]
+    fn fractional_hours_drop_trailing_zeros() {
+        assert_eq!(format_hours(5 * 3600 + 45 * 60), "+5.75h");
+        assert_eq!(format_hours(5 * 3600 + 30 * 60), "+5.5h");
+        assert_eq!(format_hours(-1800), "-0.5h");
+        assert_eq!(format_hours(-(9 * 3600 + 30 * 60)), "-9.5h");
+    }
+}
@@ -0,0 +1,114 @@
+mod convert_time;
+mod system_time;
+
+use crate::protocol::ProtocolVersion;
+use async_trait::async_trait;
+use chrono_tz::Tz;
+use indexmap::IndexMap;
+use serde_json::Value;
+
+pub use convert_time::ConvertTime;
+pub use system_time::SystemTime;
+
+// --- Tool Trait ---
+
+/// Failure of a `tools/call`, reported to the client as a JSON-RPC error.
+#[derive(Debug)]
+pub enum ToolError {
+    /// The arguments did not match the tool's input schema (-32602).
+    InvalidParams(String),
+}
+
+/// An MCP tool: the metadata advertised by `tools/list` plus the call itself.
+#[async_trait]
+pub trait Tool: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    /// JSON Schema for the `arguments` object.
+    fn input_schema(&self) -> Value;
+    /// JSON Schema for `structuredContent`, for tools that return it.
+    fn output_schema(&self) -> Option<Value> {
+        None
+    }
+    /// Behaviour hints (`title`, `readOnlyHint`, ...).
+    fn annotations(&self) -> Value;
+    /// Runs the tool and returns the `CallToolResult` object.
+    async fn call(&self, arguments: Value) -> Result<Value, ToolError>;
+}
+
+// --- Tool Registry ---
+
+/// Registered tools, kept in registration order for `tools/list`.
+#[derive(Default)]
+pub struct ToolRegistry {
+    tools: IndexMap<&'static str, Box<dyn Tool>>,
+}
+
+impl ToolRegistry {
+    /// Registry with every tool this server ships.
+    pub fn with_builtin_tools() -> Self {
+        let mut registry = ToolRegistry::default();
+        registry.register(SystemTime);
+        registry.register(ConvertTime);
+        registry
+    }
+
+    pub fn register(&mut self, tool: impl Tool + 'static) {
+        self.tools.insert(tool.name(), Box::new(tool));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Tool> {
+        self.tools.get(name).map(AsRef::as_ref)
+    }
+
+    /// The `tools` array of a `tools/list` result, limited to the fields the
+    /// negotiated revision defines.
+    pub fn list(&self, version: ProtocolVersion) -> Value {
+        self.tools
+            .values()
+            .map(|tool| {
+                let mut entry = serde_json::json!({
+                    "name": tool.name(),
+                    "description": tool.description(),
+                    "inputSchema": tool.input_schema()
+                });
+                if version.supports_tool_annotations() {
+                    entry["annotations"] = tool.annotations();
+                }
+                if let Some(schema) = tool
+                    .output_schema()
+                    .filter(|_| version.supports_structured_output())
+                {
+                    entry["outputSchema"] = schema;
+                }
+                entry
+            })
+            .collect()
+    }
+}
+
+// --- Shared Helpers ---
+
+/// Resolves a timezone argument against the bundled IANA database.
+/// An empty name and any casing of "UTC" map to UTC.
+fn parse_timezone(name: &str) -> Option<Tz> {
+    if name.is_empty() || name.eq_ignore_ascii_case("UTC") {
+        return Some(Tz::UTC);
+    }
+    name.parse::<Tz>().ok()
+}
+
+/// Builds a successful `CallToolResult` from its content items.
+fn call_result(content: Vec<Value>) -> Value {
+    let mut result = serde_json::json!({ "isError": false });
+    result["content"] = Value::Array(content);
+    result
+}
+
+/// Like `call_result`, also carrying `structuredContent` matching the tool's
+/// `outputSchema`.
+fn structured_result(content: Vec<Value>, structured: Value) -> Value {
+    let mut result = call_result(content);
+    result["structuredContent"] = structured;
+    result
+}
@@ -0,0 +1,220 @@
+use super::{parse_timezone, structured_result, Tool, ToolError};
+use async_trait::async_trait;
+use chrono::format::StrftimeItems;
+use chrono::{DateTime, Utc};
+use chrono_tz::{OffsetComponents, OffsetName, Tz};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt::Write;
+
+/// `get_system_time`: the current time in a given IANA timezone.
+pub struct SystemTime;
+
+#[derive(Deserialize, Debug)]
+struct ToolArguments {
+    #[serde(default)]
+    timezone: String,
+    #[serde(default)]
+    format: TimeFormat,
+    /// Fractional second digits for `rfc3339`.
+    #[serde(default)]
+    precision: u8,
+    /// strftime pattern for `custom`.
+    pattern: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum TimeFormat {
+    #[default]
+    Rfc3339,
+    Rfc2822,
+    UnixSeconds,
+    UnixMillis,
+    UnixNanos,
+    Custom,
+}
+
+#[async_trait]
+impl Tool for SystemTime {
+    fn name(&self) -> &'static str {
+        "get_system_time"
+    }
+
+    fn description(&self) -> &'static str {
+        "Get current system time in specified timezone"
+    }
+
+    fn input_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "timezone": {
+                    "type": "string",
+                    "description": "IANA timezone name (e.g., 'America/New_York', 'Europe/London'). Defaults to UTC"
+                },
+                "format": {
+                    "type": "string",
+                    "enum": ["rfc3339", "rfc2822", "unix_seconds", "unix_millis", "unix_nanos", "custom"],
+                    "default": "rfc3339",
+                    "description": "Output format. Unix formats are integers since the epoch and ignore the timezone offset; 'custom' requires 'pattern'"
+                },
+                "precision": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "maximum": 9,
+                    "default": 0,
+                    "description": "Number of fractional second digits for the 'rfc3339' format"
+                },
+                "pattern": {
+                    "type": "string",
+                    "description": "strftime pattern for the 'custom' format (e.g., '%Y-%m-%d %H:%M:%S %Z')"
+                }
+            }
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "iso": {
+                    "type": "string",
+                    "format": "date-time",
+                    "description": "RFC 3339 timestamp in the requested timezone, with 'precision' fractional digits"
+                },
+                "unix": {
+                    "type": "integer",
+                    "description": "Seconds since the Unix epoch"
+                },
+                "timezone": {
+                    "type": "string",
+                    "description": "IANA timezone name the time was resolved in"
+                },
+                "utc_offset": {
+                    "type": "string",
+                    "description": "Offset from UTC as +HH:MM or -HH:MM"
+                },
+                "is_dst": {
+                    "type": "boolean",
+                    "description": "Whether daylight saving time is in effect"
+                }
+            },
+            "required": ["iso", "unix", "timezone", "utc_offset", "is_dst"]
+        }))
+    }
+
+    fn annotations(&self) -> Value {
+        serde_json::json!({
+            "title": "Get System Time",
+            "readOnlyHint": true,
+            "destructiveHint": false,
+            "idempotentHint": false,
+            "openWorldHint": false
+        })
+    }
+
+    async fn call(&self, arguments: Value) -> Result<Value, ToolError> {
+        let args: ToolArguments = serde_json::from_value(arguments).map_err(|e| {
+            ToolError::InvalidParams(format!("Invalid params for get_system_time: {e}"))
+        })?;
+        let tz = parse_timezone(&args.timezone).ok_or_else(|| {
+            ToolError::InvalidParams(format!(
+                "Invalid params: unknown timezone '{}'",
+                args.timezone
+            ))
+        })?;
+
+        let now = Utc::now().with_timezone(&tz);
+        let mut content = vec![serde_json::json!({
+            "type": "text",
+            "text": format_time(&now, &args)?
+        })];
+        // UTC keeps the single-item response so benchmarks stay comparable
+        // with fast-time-server; named zones also report offset and DST.
+        if tz != Tz::UTC {
+            content.push(serde_json::json!({
+                "type": "text",
+                "text": describe_zone(&now)
+            }));
+        }
+        let structured = serde_json::json!({
+            "iso": format_rfc3339(&now, args.precision)?,
+            "unix": now.timestamp(),
+            "timezone": tz.name(),
+            "utc_offset": now.format("%:z").to_string(),
+            "is_dst": !now.offset().dst_offset().is_zero()
+        });
+        Ok(structured_result(content, structured))
+    }
+}
+
+fn format_time(time: &DateTime<Tz>, args: &ToolArguments) -> Result<String, ToolError> {
+    match args.format {
+        TimeFormat::Rfc3339 => format_rfc3339(time, args.precision),
+        TimeFormat::Rfc2822 => Ok(time.to_rfc2822()),
+        TimeFormat::UnixSeconds => Ok(time.timestamp().to_string()),
+        TimeFormat::UnixMillis => Ok(time.timestamp_millis().to_string()),
+        // Out of range only past the year 2262.
+        TimeFormat::UnixNanos => Ok(time.timestamp_nanos_opt().unwrap_or_default().to_string()),
+        TimeFormat::Custom => {
+            let pattern = args.pattern.as_deref().ok_or_else(|| {
+                ToolError::InvalidParams(
+                    "Invalid params: 'pattern' is required for the custom format".to_string(),
+                )
+            })?;
+            format_strftime(time, pattern)
+        }
+    }
+}
+
+// chrono's fixed `SecondsFormat` variants only cover 0/3/6/9 digits, so the
+// fraction is truncated from the nanoseconds by hand.
+fn format_rfc3339(time: &DateTime<Tz>, precision: u8) -> Result<String, ToolError> {
+    if precision > 9 {
+        return Err(ToolError::InvalidParams(
+            "Invalid params: precision must be between 0 and 9".to_string(),
+        ));
+    }
+    let mut formatted = time.format("%Y-%m-%dT%H:%M:%S").to_string();
+    if precision > 0 {
+        let nanos = format!("{:09}", time.timestamp_subsec_nanos() % 1_000_000_000);
+        formatted.push('.');
+        formatted.push_str(&nanos[..usize::from(precision)]);
+    }
+    if time.timezone() == Tz::UTC {
+        formatted.push('Z');
+    } else {
+        formatted.push_str(&time.format("%:z").to_string());
+    }
+    Ok(formatted)
+}
+
+fn format_strftime(time: &DateTime<Tz>, pattern: &str) -> Result<String, ToolError> {
+    let items = StrftimeItems::new(pattern).parse().map_err(|_| {
+        ToolError::InvalidParams(format!("Invalid params: bad strftime pattern '{pattern}'"))
+    })?;
+    let mut formatted = String::new();
+    write!(formatted, "{}", time.format_with_items(items.iter())).map_err(|_| {
+        ToolError::InvalidParams(format!(
+            "Invalid params: pattern '{pattern}' cannot be rendered"
+        ))
+    })?;
+    Ok(formatted)
+}
+
+// e.g. "America/New_York EDT (UTC-04:00, DST in effect)"
+fn describe_zone(time: &DateTime<Tz>) -> String {
+    let offset = time.offset();
+    let abbreviation = offset.abbreviation().unwrap_or_default();
+    let dst = if offset.dst_offset().is_zero() {
+        "standard time"
+    } else {
+        "DST in effect"
+    };
+    format!(
+        "{} {abbreviation} (UTC{}, {dst})",
+        time.timezone().name(),
+        time.format("%:z")
+    )
+}